@@ -6,11 +6,12 @@ use serde::{Deserialize, Serialize};
 use serde_json;
 use std::env;
 use verity_client::client::{VerityClient, VerityClientConfig};
+use verity_client::Redaction;
 use verity_verify_remote::{
     config::Config,
     ic::{Verifier, DEFAULT_IC_GATEWAY_LOCAL},
 };
-use verity_verify_tls::verify_proof;
+use verity_verify_tls::VerificationMode;
 // use verity_dp_zk_host::generate_groth16_proof;
 
 pub const DEFAULT_PROVER_URL: &str = "http://127.0.0.1:8080";
@@ -63,7 +64,7 @@ async fn main() -> anyhow::Result<()> {
 
     let result = client
         .get("https://jsonplaceholder.typicode.com/posts/98")
-        .redact(String::from("res:body:dolor"))
+        .redact(Redaction::response_json("dolor"))
         .send()
         .await;
 
@@ -105,9 +106,20 @@ async fn main() -> anyhow::Result<()> {
 
     // Totally optional to verify proof on host too.
     // For the sake of this demo, we'll verify the proof on the host to ensure both the zkVM and host agree to the verification.
-    let verified_by_host: (String, String) =
-        verify_proof(&response.proof, &notary_pub_key).unwrap();
+    let verified_by_host: (String, String) = VerificationMode::Session {
+        notary_pub_key: notary_pub_key.clone(),
+    }
+    .verify_proof(&response.proof)
+    .unwrap();
     println!("verified_by_host: {:#?}", verified_by_host);
+
+    // The guest only verifies the substrings, which the host can check up front before
+    // spending time on proving.
+    let verified_like_guest = VerificationMode::NoSession
+        .verify_proof(&response.proof)
+        .unwrap();
+    assert_eq!(verified_like_guest, verified_by_host);
+
     // Perform the partial remote verification against decentralised compute
 
     // 1. Create a config file by specifying the params
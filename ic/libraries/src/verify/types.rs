@@ -3,6 +3,7 @@ use candid::CandidType;
 use httparse;
 use serde::Deserialize;
 use std::{collections::HashMap, vec};
+use verity_verify_tls::{parse_http_exchanges, RedactedBytes, VerifiedHttpExchange};
 
 /// The response from the managed verifier canister.
 /// It is a `Result` type that contains either a `VerificationResponse` on success
@@ -32,28 +33,15 @@ pub enum ProofResponse {
 
 // Implementations for the `ProofResponse` enum.
 impl ProofResponse {
-    /// Parses the HTTP response and extracts the JSON response body.
+    /// Parses the HTTP exchange and extracts the response body, with chunked
+    /// transfer-encoding decoded. Returns an empty string if the proof carries no
+    /// complete HTTP exchange.
     /// This is applicable only for `FullProof`.
     pub fn get_http_response_body(&self) -> String {
         match self {
-            ProofResponse::FullProof(text) => {
-                let http_parts: Vec<&str> = text.split("\n\n").filter(|s| !s.is_empty()).collect();
-
-                // if empty body and empty request is returned
-                if http_parts.len() == 2 {
-                    return "".to_string();
-                }
-
-                if http_parts[0].contains("application/json") {
-                    if let Some(start) = http_parts[1].find('{') {
-                        if let Some(end) = http_parts[1].rfind('}') {
-                            return http_parts[1][start..=end].to_string();
-                        }
-                    }
-                }
-
-                http_parts[1].to_string()
-            }
+            ProofResponse::FullProof(text) => parse_http_exchange(text)
+                .map(|exchange| exchange.response.body.text().into_owned())
+                .unwrap_or_default(),
             ProofResponse::SessionProof(_) => {
                 panic!("Cannot extract HTTP response for session proof")
             }
@@ -112,14 +100,51 @@ impl ProofResponse {
     }
 }
 
+/// Parses the exchange of a full proof, whose request and response transcripts are
+/// joined by an empty line. Each line break that could join them is tried, with the
+/// transcripts in either order, until they parse as exactly one HTTP exchange.
+fn parse_http_exchange(text: &str) -> Option<VerifiedHttpExchange> {
+    let transcript = |text: &str| RedactedBytes {
+        data: text.as_bytes().to_vec(),
+        redacted: vec![false; text.len()],
+    };
+
+    text.as_bytes()
+        .windows(2)
+        .enumerate()
+        .filter(|(_, pair)| pair == b"\n\n")
+        .find_map(|(split, _)| {
+            let (first, second) = (&text[..split], &text[split + 2..]);
+            [(first, second), (second, first)]
+                .into_iter()
+                .find_map(|(sent, recv)| {
+                    let mut exchanges =
+                        parse_http_exchanges(&transcript(sent), &transcript(recv)).ok()?;
+                    (exchanges.len() == 1).then(|| exchanges.remove(0))
+                })
+        })
+}
+
 #[cfg(test)]
 mod type_test {
     use super::*;
 
+    /// A notarized exchange, with the heads written one header per line.
+    struct TestCase {
+        response_head: &'static str,
+        /// The response body as sent, with any chunked framing.
+        response_body: &'static str,
+        request_head: &'static str,
+        request_body: &'static str,
+        /// The decoded response body.
+        body: &'static str,
+        status: &'static str,
+    }
+
     // Define shared test cases to avoid duplication
-    const TEST_CASES: &[(&str, &str, &str)] = &[
-        (
-            r#"HTTP/1.1 200 OK
+    const TEST_CASES: &[TestCase] = &[
+        TestCase {
+            response_head: r#"HTTP/1.1 200 OK
 Date: Mon, 10 Feb 2025 23:41:20 GMT
 Content-Type: application/json; charset=utf-8
 Transfer-Encoding: chunked
@@ -146,30 +171,25 @@ CF-Cache-Status: HIT
 Age: 120
 Server: cloudflare
 CF-RAY: 90fff30a9c833e9a-CPT
-alt-svc: h3=":443"; ma=86400
-
-19
-{"bitcoin":{"usd":97334}}
-0
-
-GET https://api.coingecko.com/api/v3/simple/price?ids=bitcoin&vs_currencies=usd HTTP/1.1
+alt-svc: h3=":443"; ma=86400"#,
+            response_body: "19\r\n{\"bitcoin\":{\"usd\":97334}}\r\n0\r\n\r\n",
+            request_head: r#"GET https://api.coingecko.com/api/v3/simple/price?ids=bitcoin&vs_currencies=usd HTTP/1.1
 host: api.coingecko.com
 accept: */*
 cache-control: no-cache
 connection: close
 accept-encoding: identity
 content-type: application/json
-x-api-key: XXXXXX
-
-"#,
-            r#"{"bitcoin":{"usd":97334}}"#,
-            "200",
-        ),
-        (
-            r#"HTTP/1.1 200 OK
+x-api-key: XXXXXX"#,
+            request_body: "",
+            body: r#"{"bitcoin":{"usd":97334}}"#,
+            status: "200",
+        },
+        TestCase {
+            response_head: r#"HTTP/1.1 200 OK
 Date: Mon, 10 Feb 2025 23:56:29 GMT
 Content-Type: application/json
-Content-Length: 779
+Content-Length: 725
 Connection: close
 openai-version: 2020-10-01
 x-request-id: 5d83f65c8716a7a2d5316861b158eacb
@@ -181,9 +201,8 @@ X-Content-Type-Options: nosniff
 Set-Cookie: _cfuvid=mHKpBMPOySOFR4Ddm2eyW00LQC13wBIguaVCkLhXkGg-1739231789707-0.0.1.1-604800000; path=/; domain=.api.openai.com; HttpOnly; Secure; SameSite=None
 Server: cloudflare
 CF-RAY: 9100093abb6206cf-CPT
-alt-svc: h3=":443"; ma=86400
-
-{
+alt-svc: h3=":443"; ma=86400"#,
+            response_body: r#"{
 "object": "list",
 "data": [
     {
@@ -223,18 +242,17 @@ alt-svc: h3=":443"; ma=86400
     "owned_by": "system"
     }
 ]
-}
-
-GET https://api.openai.com/v1/models HTTP/1.1
+}"#,
+            request_head: r#"GET https://api.openai.com/v1/models HTTP/1.1
 host: api.openai.com
 accept: */*
 cache-control: no-cache
 connection: close
 accept-encoding: identity
 content-type: application/json
-authorization: XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX
-"#,
-            r#"{
+authorization: XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX"#,
+            request_body: "",
+            body: r#"{
 "object": "list",
 "data": [
     {
@@ -275,14 +293,13 @@ authorization: XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX
     }
 ]
 }"#,
-            "200",
-        ),
-        (
-            r#"
-HTTP/1.1 201 Created
+            status: "200",
+        },
+        TestCase {
+            response_head: r#"HTTP/1.1 201 Created
 Date: Fri, 21 Jun 2024 12:35:32 GMT
 Content-Type: application/json; charset=utf-8
-Content-Length: 69
+Content-Length: 61
 Connection: close
 Report-To: {"group":"heroku-nel","max_age":3600,"endpoints":[{"url":"https://nel.heroku.com/reports?ts=1718973332&sid=e11707d5-02a7-43ef-b45e-2cf4d2036f7d&s=ho9mNnYMVvORRQ3gpBnbquUgUKERGKIM6Bu5rk5iTUc%3D"}]}
 Reporting-Endpoints: heroku-nel=https://nel.heroku.com/reports?ts=1718973332&sid=e11707d5-02a7-43ef-b45e-2cf4d2036f7d&s=ho9mNnYMVvORRQ3gpBnbquUgUKERGKIM6Bu5rk5iTUc%3D
@@ -304,16 +321,14 @@ Via: 1.1 vegur
 CF-Cache-Status: DYNAMIC
 Server: cloudflare
 CF-RAY: 897409fb9a6d1963-FRA
-alt-svc: h3=":443"; ma=86400
-
-{
+alt-svc: h3=":443"; ma=86400"#,
+            response_body: r#"{
 "title": "usher",
 "body": "labs",
 "userId": XX,
 "id": XX1
-}
-
-POST https://jsonplaceholder.typicode.com/posts HTTP/1.1
+}"#,
+            request_head: r#"POST https://jsonplaceholder.typicode.com/posts HTTP/1.1
 host: jsonplaceholder.typicode.com
 accept: */*
 cache-control: no-cache
@@ -321,21 +336,18 @@ connection: close
 accept-encoding: identity
 x-api-key: XXXXXX
 content-type: application/json
-content-length: 48
-
-{"title": "usher", "body": "labs", "userId": XX}
-"#,
-            r#"{
+content-length: 48"#,
+            request_body: r#"{"title": "usher", "body": "labs", "userId": XX}"#,
+            body: r#"{
 "title": "usher",
 "body": "labs",
 "userId": XX,
 "id": XX1
 }"#,
-            "201",
-        ),
-        (
-            r#"
-HTTP/1.1 200 OK
+            status: "201",
+        },
+        TestCase {
+            response_head: r#"HTTP/1.1 200 OK
 Date: Mon, 10 Feb 2025 23:36:49 GMT
 Content-Type: application/json; charset=utf-8
 Transfer-Encoding: chunked
@@ -362,27 +374,36 @@ CF-Cache-Status: HIT
 Age: 216
 Server: cloudflare
 CF-RAY: 90ffec6a999e0710-CPT
-alt-svc: h3=":443"; ma=86400
-
-19
-{"bitcoin":{"usd":97281}}
-0
-
-GET https://api.coingecko.com/api/v3/simple/price?ids=bitcoin&vs_currencies=usd HTTP/1.1
+alt-svc: h3=":443"; ma=86400"#,
+            response_body: "19\r\n{\"bitcoin\":{\"usd\":97281}}\r\n0\r\n\r\n",
+            request_head: r#"GET https://api.coingecko.com/api/v3/simple/price?ids=bitcoin&vs_currencies=usd HTTP/1.1
 host: api.coingecko.com
 accept: */*
 cache-control: no-cache
 connection: close
 accept-encoding: identity
 content-type: application/json
-x-api-key: XXXXXX
-content-length: 48
-"#,
-            r#"{"bitcoin":{"usd":97281}}"#,
-            "200",
-        ),
+x-api-key: XXXXXX"#,
+            request_body: "",
+            body: r#"{"bitcoin":{"usd":97281}}"#,
+            status: "200",
+        },
     ];
 
+    /// Serializes one message, ending the lines of its head with CRLF.
+    fn message(head: &str, body: &str) -> String {
+        format!("{}\r\n\r\n{}", head.replace('\n', "\r\n"), body)
+    }
+
+    /// The content of a full proof for a test case, with the response first.
+    fn full_proof(case: &TestCase) -> String {
+        format!(
+            "{}\n\n{}",
+            message(case.response_head, case.response_body),
+            message(case.request_head, case.request_body)
+        )
+    }
+
     #[test]
     fn test_verification_canister_response_success() {
         let proof = ProofResponse::SessionProof("hashed_content".to_string());
@@ -418,17 +439,27 @@ content-length: 48
 
     #[test]
     fn test_proof_response_get_http_response_body_full_proof_json() {
-        for (input, expected, _) in TEST_CASES {
-            let proof = ProofResponse::FullProof(input.to_string());
+        for case in TEST_CASES {
+            let proof = ProofResponse::FullProof(full_proof(case));
             let http_body = proof.get_http_response_body();
-            assert_eq!(http_body, *expected);
+            assert_eq!(http_body, case.body);
+
+            // The managed verifier writes the request first
+            let request_first = format!(
+                "{}\n\n{}",
+                message(case.request_head, case.request_body),
+                message(case.response_head, case.response_body)
+            );
+            let proof = ProofResponse::FullProof(request_first);
+            assert_eq!(proof.get_http_response_body(), case.body);
         }
     }
 
     #[test]
     fn test_proof_response_get_http_response_header_full_proof_json() {
-        for (input, expected, status_code) in TEST_CASES {
-            let proof = ProofResponse::FullProof(input.to_string());
+        for case in TEST_CASES {
+            let input = full_proof(case);
+            let proof = ProofResponse::FullProof(input.clone());
             let headers_result = proof.get_http_headers();
             assert!(
                 headers_result.is_ok(),
@@ -446,7 +477,7 @@ content-length: 48
                 "Content-Type header mismatch for input: {}",
                 input
             );
-            assert_eq!(headers.get("Status").unwrap(), status_code);
+            assert_eq!(headers.get("Status").unwrap(), case.status);
         }
     }
 }
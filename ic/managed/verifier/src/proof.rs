@@ -58,13 +58,15 @@ impl ProofRequest {
         match self {
             // verify the session proof and return a hash of the input as a response
             ProofRequest::SessionProof(proof_string) => {
-                let _ = verify_session(&proof_string, &notary_pub_key)?;
+                let _ =
+                    verify_session(&proof_string, &notary_pub_key).map_err(|e| e.to_string())?;
                 let response = hash(&proof_string);
                 Ok(ProofResponse::SessionProof(response))
             }
             // verify the full proof and return the request/response pair
             ProofRequest::FullProof(proof_string) => {
-                let (res, req) = verify_proof(&proof_string, &notary_pub_key)?;
+                let (res, req) =
+                    verify_proof(&proof_string, &notary_pub_key).map_err(|e| e.to_string())?;
                 let response = format!("{}\n\n{}", req, res);
                 Ok(ProofResponse::FullProof(response))
             }
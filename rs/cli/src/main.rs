@@ -0,0 +1,288 @@
+//! `verity`, a command-line tool for proving requests through a Verity prover and
+//! verifying the resulting proofs.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use candid::Principal;
+use clap::{Parser, Subcommand};
+use verity_client::client::{VerityClient, VerityClientConfig};
+use verity_client::{ProofBundle, Redaction};
+use verity_verify_remote::config::Config;
+use verity_verify_remote::ic::{Verifier, DEFAULT_IC_GATEWAY_MAINNET};
+
+#[derive(Debug, Parser)]
+#[command(name = "verity", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Sends a request through a prover and writes its proof to a file
+    Prove {
+        /// The URL to request
+        url: String,
+        /// The prover to send the request through
+        #[arg(
+            long,
+            env = "VERITY_PROVER_URL",
+            default_value = "http://127.0.0.1:8080"
+        )]
+        prover: String,
+        /// The HTTP method
+        #[arg(short = 'X', long, default_value = "GET")]
+        method: String,
+        /// A header to send, as `Name: value`. Can be repeated
+        #[arg(short = 'H', long = "header", value_parser = parse_header)]
+        headers: Vec<(String, String)>,
+        /// The request body
+        #[arg(short, long)]
+        data: Option<String>,
+        /// A redaction rule, as `<target>:<kind>:<value>` (e.g. `res:body:data.token`).
+        /// Can be repeated
+        #[arg(short, long = "redact", value_parser = parse_redaction)]
+        redactions: Vec<Redaction>,
+        /// Proves the response even if its status is not a success
+        #[arg(long)]
+        prove_failed: bool,
+        /// Seconds to wait for the proof
+        #[arg(long, default_value_t = 30)]
+        timeout: u64,
+        /// Where to write the proof bundle
+        #[arg(short, long, default_value = "proof.json")]
+        output: PathBuf,
+        /// Writes the proof bundle in its compact binary encoding instead of JSON
+        #[arg(long)]
+        binary: bool,
+    },
+    /// Verifies a proof file offline and prints the revealed transcript
+    Verify {
+        /// The proof bundle written by `verity prove`
+        proof: PathBuf,
+        /// A PEM file with the public key of a notary you trust. The key stored in the
+        /// proof bundle is not trusted, since anyone can sign a bundle with their own key
+        #[arg(long)]
+        notary_key: PathBuf,
+    },
+    /// Verifies proof bundles through the managed verifier canister
+    VerifyRemote {
+        /// The proof bundles written by `verity prove`
+        #[arg(required = true)]
+        proofs: Vec<PathBuf>,
+        /// The principal of the verifier canister
+        #[arg(long, env = "VERITY_VERIFIER_CANISTER")]
+        canister: String,
+        /// The PEM file of the identity calling the canister
+        #[arg(long, env = "VERITY_IDENTITY", default_value = "identity.pem")]
+        identity: PathBuf,
+        /// The IC gateway to connect to
+        #[arg(long, env = "VERITY_IC_URL", default_value = DEFAULT_IC_GATEWAY_MAINNET)]
+        ic_url: String,
+    },
+    /// Prints the notary information of a prover
+    NotaryInfo {
+        /// The prover to query
+        #[arg(
+            long,
+            env = "VERITY_PROVER_URL",
+            default_value = "http://127.0.0.1:8080"
+        )]
+        prover: String,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    match Cli::parse().command {
+        Command::Prove {
+            url,
+            prover,
+            method,
+            headers,
+            data,
+            redactions,
+            prove_failed,
+            timeout,
+            output,
+            binary,
+        } => {
+            let config =
+                VerityClientConfig::new(prover).with_proof_timeout(Duration::from_secs(timeout));
+            let method = method.to_uppercase();
+            let parsed = method
+                .parse()
+                .with_context(|| format!("invalid method {}", method))?;
+
+            let client = VerityClient::new(config);
+            let mut request = client.request(parsed, &url);
+            for (name, value) in headers {
+                request = request.header(name, value);
+            }
+            if let Some(data) = data {
+                request = request.body(data);
+            }
+            for redaction in redactions {
+                request = request.redact(redaction);
+            }
+            if prove_failed {
+                request = request.prove_failed_request();
+            }
+
+            let response = request.send().await?;
+            let status = response.subject.status();
+            if response.proof.is_empty() {
+                bail!("the prover did not prove the response ({})", status);
+            }
+
+            // The proof is already made, so failing to fetch the notary information only
+            // leaves it out of the bundle
+            let mut bundle = response.to_bundle();
+            match client.get_notary_info().await {
+                Ok(info) => bundle = bundle.with_notary_info(info),
+                Err(e) => eprintln!("warning: could not fetch the notary information: {}", e),
+            }
+            let content = if binary {
+                bundle.to_bytes()?
+            } else {
+                bundle.to_json()?.into_bytes()
+            };
+            fs::write(&output, content).with_context(|| format!("writing {}", output.display()))?;
+
+            eprintln!("{} proven, proof written to {}", status, output.display());
+            print!("{}", response.subject.text().await?);
+        }
+        Command::Verify { proof, notary_key } => {
+            let bundle = read_bundle(&proof)?;
+            let notary_key = fs::read_to_string(&notary_key)
+                .with_context(|| format!("reading {}", notary_key.display()))?;
+            let (recv, sent) = verity_verify_tls::verify_proof(&bundle.proof_json()?, &notary_key)
+                .context("verification failed")?;
+
+            println!("{}\n\n{}", sent, recv);
+        }
+        Command::VerifyRemote {
+            proofs,
+            canister,
+            identity,
+            ic_url,
+        } => {
+            let bundles = proofs
+                .iter()
+                .map(|path| read_bundle(path))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            Principal::from_text(&canister)
+                .with_context(|| format!("invalid canister principal {}", canister))?;
+
+            let config = Config::new(ic_url, identity.display().to_string(), canister);
+            let verifier = Verifier::from_config(&config).await?;
+            let response = verifier
+                .verify_bundles(&bundles)
+                .await
+                .map_err(|e| anyhow::anyhow!("verification failed: {}", e))?;
+
+            println!("root: {}", response.root);
+            println!("signature: {}", response.signature);
+            for result in response.results {
+                println!("\n{}", result.get_content());
+            }
+        }
+        Command::NotaryInfo { prover } => {
+            let client = VerityClient::new(VerityClientConfig::new(prover));
+            let info = client.get_notary_info().await?;
+            println!("{}", serde_json::to_string_pretty(&info)?);
+        }
+    }
+
+    Ok(())
+}
+
+fn read_bundle(path: &Path) -> anyhow::Result<ProofBundle> {
+    let content = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    ProofBundle::decode(&content).with_context(|| format!("parsing {}", path.display()))
+}
+
+fn parse_header(value: &str) -> Result<(String, String), String> {
+    let (name, value) = value
+        .split_once(':')
+        .ok_or_else(|| format!("expected `Name: value`, got `{}`", value))?;
+    Ok((name.trim().to_string(), value.trim().to_string()))
+}
+
+fn parse_redaction(value: &str) -> Result<Redaction, String> {
+    let mut rules = Redaction::parse_header_value(value).map_err(|e| e.to_string())?;
+    match rules.len() {
+        1 => Ok(rules.remove(0)),
+        _ => Err(format!("expected a single rule, got `{}`", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn parses_headers() {
+        assert_eq!(
+            parse_header("X-Api-Key: a:b").unwrap(),
+            ("X-Api-Key".to_string(), "a:b".to_string())
+        );
+        assert!(parse_header("X-Api-Key").is_err());
+    }
+
+    #[test]
+    fn parses_redactions() {
+        assert_eq!(
+            parse_redaction("res:body:data.token").unwrap(),
+            Redaction::response_json("data.token")
+        );
+        assert!(parse_redaction("res:body:a, req:header:b").is_err());
+    }
+
+    #[test]
+    fn parses_commands() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::parse_from([
+            "verity",
+            "prove",
+            "https://example.com",
+            "-X",
+            "post",
+            "-H",
+            "Accept: application/json",
+            "-r",
+            "req:header:authorization",
+        ]);
+        let Command::Prove {
+            method,
+            headers,
+            redactions,
+            ..
+        } = cli.command
+        else {
+            panic!("expected prove");
+        };
+        assert_eq!(method, "post");
+        assert_eq!(headers.len(), 1);
+        assert_eq!(redactions, [Redaction::request_header("authorization")]);
+    }
+
+    #[test]
+    fn verify_requires_a_notary_key() {
+        assert!(Cli::try_parse_from(["verity", "verify", "proof.json"]).is_err());
+        assert!(Cli::try_parse_from([
+            "verity",
+            "verify",
+            "proof.json",
+            "--notary-key",
+            "notary.pub"
+        ])
+        .is_ok());
+    }
+}
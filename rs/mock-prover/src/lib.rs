@@ -0,0 +1,323 @@
+//! An in-process Verity prover for tests.
+//!
+//! [`MockProver`] serves the `/proxy`, `/proof/:id` and `/notaryinfo` endpoints of a
+//! Verity prover. Proofs are delivered over every `ProofTransport` the client supports,
+//! compressed with the first supported encoding offered in `T-PROOF-ENCODING`. Proxied requests are forwarded to their target over plain HTTP and
+//! the exchange is notarized in-process, so the proofs it returns are real TLSNotary
+//! session proofs, signed with the test key [`NOTARY_SIGNING_KEY`].
+//!
+//! The server certificate in a proof is issued for the target host by a test
+//! certificate authority, so full verification must trust [`root_certificate`] instead
+//! of the `webpki-roots`, as `verity_verify_tls::verify_proof_bytes_with_roots` does with
+//! the `test-roots` feature of `verity-verify-tls`.
+//! Targets addressed by an IP address are notarized as `localhost`, since a TLS server
+//! name is a DNS name. `verity_verify_tls::no_session::verify_proof` accepts the proofs
+//! as they are.
+
+mod notary;
+mod tls;
+mod transcript;
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::body::Bytes;
+use axum::extract::ws::{Message, WebSocketUpgrade};
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+use axum::response::sse::{Event, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{any, get};
+use axum::{Json, Router};
+use futures::stream;
+use tokio::net::TcpListener;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use verity_client::client::NotaryInformation;
+use verity_client::redaction::{Redaction, RedactionTarget};
+use verity_client::ProofEncoding;
+
+use crate::transcript::HttpMessage;
+
+pub use notary::{NOTARY_PUBLIC_KEY, NOTARY_SIGNING_KEY, NOTARY_VERSION};
+pub use tls::root_certificate;
+
+/// Headers describing message framing, which are recomputed for the notarized response.
+const FRAMING_HEADERS: [&str; 4] = [
+    "connection",
+    "content-length",
+    "keep-alive",
+    "transfer-encoding",
+];
+
+/// How long a long poll for a proof is held before answering `204 No Content`.
+const LONG_POLL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A prover listening on a random local port. The server stops when dropped.
+pub struct MockProver {
+    addr: SocketAddr,
+    server: JoinHandle<()>,
+}
+
+impl MockProver {
+    /// Starts a prover on `127.0.0.1`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if no local port can be bound.
+    pub async fn start() -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let app = router();
+        let server = tokio::spawn(async move {
+            axum::serve(listener, app.into_make_service())
+                .await
+                .expect("mock prover server failed");
+        });
+
+        Ok(Self { addr, server })
+    }
+
+    /// The URL to configure as `prover_url`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// The PEM encoded public key of the mock notary.
+    pub fn notary_public_key(&self) -> &'static str {
+        NOTARY_PUBLIC_KEY
+    }
+
+    /// The DER encoded root certificate the server certificates in proofs chain to.
+    pub fn root_certificate(&self) -> Vec<u8> {
+        root_certificate()
+    }
+}
+
+impl Drop for MockProver {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+/// Shared state of the prover endpoints.
+#[derive(Clone, Default)]
+struct ProverState {
+    http_client: reqwest::Client,
+    proofs: Arc<Mutex<HashMap<String, String>>>,
+    proof_ready: Arc<Notify>,
+}
+
+impl ProverState {
+    /// Waits until the request `id` is notarized and returns its `pubkey|proof` message,
+    /// with the proof compressed with `encoding` if set.
+    async fn wait_for_proof(&self, id: &str, encoding: Option<ProofEncoding>) -> String {
+        let message = loop {
+            // Registered before checking, so a proof stored in between is not missed
+            let notified = self.proof_ready.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if let Some(message) = self.proofs.lock().unwrap().get(id).cloned() {
+                break message;
+            }
+
+            notified.await;
+        };
+
+        match encoding {
+            Some(encoding) => {
+                let (key, proof) = message.split_once('|').expect("stored proof message");
+                let proof = encoding.encode(proof).expect("proof compression failed");
+                format!("{}|{}", key, proof)
+            }
+            None => message,
+        }
+    }
+}
+
+/// The prover endpoints, for embedding into an existing test server.
+pub fn router() -> Router {
+    Router::new()
+        .route("/proxy", any(proxy))
+        .route("/proof/:id", get(proof))
+        .route("/notaryinfo", get(notary_info))
+        .with_state(ProverState::default())
+}
+
+async fn notary_info() -> Json<NotaryInformation> {
+    Json(NotaryInformation {
+        version: NOTARY_VERSION.to_string(),
+        public_key: NOTARY_PUBLIC_KEY.to_string(),
+        git_commit_hash: "0000000000000000000000000000000000000000".to_string(),
+        git_commit_timestamp: "0".to_string(),
+    })
+}
+
+/// Forwards the request to `T-PROXY-URL` and notarizes the exchange.
+async fn proxy(
+    State(state): State<ProverState>,
+    method: Method,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, (StatusCode, String)> {
+    let bad_request = |message: String| (StatusCode::BAD_REQUEST, message);
+
+    let target = header_str(&headers, "T-PROXY-URL")
+        .ok_or_else(|| bad_request("missing T-PROXY-URL".to_string()))?;
+    let target = reqwest::Url::parse(target).map_err(|e| bad_request(e.to_string()))?;
+    let request_id = header_str(&headers, "T-REQUEST-ID")
+        .ok_or_else(|| bad_request("missing T-REQUEST-ID".to_string()))?
+        .to_string();
+    let redactions = match header_str(&headers, "T-REDACTED") {
+        Some(value) => {
+            Redaction::parse_header_value(value).map_err(|e| bad_request(e.to_string()))?
+        }
+        None => Vec::new(),
+    };
+    let prove_failed = header_str(&headers, "T-PROVE-FAILED-REQ") == Some("true");
+
+    // Everything but the prover instructions and framing is forwarded to the target
+    let forwarded: Vec<(HeaderName, HeaderValue)> = headers
+        .iter()
+        .filter(|(name, _)| {
+            !name.as_str().starts_with("t-")
+                && name.as_str() != "host"
+                && !FRAMING_HEADERS.contains(&name.as_str())
+        })
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+
+    let server_name = target.domain().unwrap_or("localhost").to_string();
+    let host = target.host_str().unwrap_or_default().to_string();
+    let host = match target.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host,
+    };
+    let content_length = body.len().to_string();
+    let mut sent_headers = vec![("host", host.as_bytes())];
+    sent_headers.extend(
+        forwarded
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_bytes())),
+    );
+    if !body.is_empty() {
+        sent_headers.push(("content-length", content_length.as_bytes()));
+    }
+    let path = match target.query() {
+        Some(query) => format!("{}?{}", target.path(), query),
+        None => target.path().to_string(),
+    };
+    let sent = HttpMessage::new(
+        &format!("{} {} HTTP/1.1", method, path),
+        sent_headers,
+        &body,
+    );
+
+    let mut request = state.http_client.request(method, target).body(body.clone());
+    for (name, value) in forwarded {
+        request = request.header(name, value);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e.to_string()))?;
+
+    let status = response.status();
+    let mut response_headers = response.headers().clone();
+    for name in FRAMING_HEADERS {
+        response_headers.remove(name);
+    }
+    let response_body = response
+        .bytes()
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e.to_string()))?;
+
+    let response_length = response_body.len().to_string();
+    let mut recv_headers: Vec<(&str, &[u8])> = response_headers
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_bytes()))
+        .collect();
+    recv_headers.push(("content-length", response_length.as_bytes()));
+    let recv = HttpMessage::new(
+        &format!(
+            "HTTP/1.1 {} {}",
+            status.as_u16(),
+            status.canonical_reason().unwrap_or_default()
+        ),
+        recv_headers,
+        &response_body,
+    );
+
+    if status.is_success() || prove_failed {
+        let proof = notary::notarize(
+            &server_name,
+            &sent.bytes,
+            &recv.bytes,
+            &sent.revealed(&redactions, RedactionTarget::Request),
+            &recv.revealed(&redactions, RedactionTarget::Response),
+        )
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+        // SSE data is a single line, so the key is sent with escaped newlines like the
+        // real prover does
+        state.proofs.lock().unwrap().insert(
+            request_id.clone(),
+            format!("{}|{}", NOTARY_PUBLIC_KEY.replace('\n', "\\n"), proof),
+        );
+        state.proof_ready.notify_waiters();
+
+        response_headers.insert(
+            "T-PROOF-ID",
+            HeaderValue::from_str(&request_id).map_err(|e| bad_request(e.to_string()))?,
+        );
+    }
+
+    Ok((status, response_headers, response_body).into_response())
+}
+
+/// Delivers the proof of a request once it is notarized, over a WebSocket when the
+/// request is an upgrade, as a long poll when it accepts `text/plain`, and as an event
+/// stream otherwise.
+async fn proof(
+    State(state): State<ProverState>,
+    Path(id): Path<String>,
+    ws: Option<WebSocketUpgrade>,
+    headers: HeaderMap,
+) -> Response {
+    let encoding = header_str(&headers, "T-PROOF-ENCODING")
+        .and_then(|offered| offered.split(',').find_map(|name| name.parse().ok()));
+
+    if let Some(ws) = ws {
+        return ws.on_upgrade(move |mut socket| async move {
+            let message = state.wait_for_proof(&id, encoding).await;
+            // Held open until the client closes the socket after reading the proof
+            if socket.send(Message::Text(message)).await.is_ok() {
+                while let Some(Ok(_)) = socket.recv().await {}
+            }
+        });
+    }
+
+    if header_str(&headers, "accept") == Some("text/plain") {
+        let message = state.wait_for_proof(&id, encoding);
+        return match tokio::time::timeout(LONG_POLL_TIMEOUT, message).await {
+            Ok(message) => message.into_response(),
+            Err(_) => StatusCode::NO_CONTENT.into_response(),
+        };
+    }
+
+    let event = async move {
+        let message = state.wait_for_proof(&id, encoding).await;
+        Ok::<_, Infallible>(Event::default().id(id).data(message))
+    };
+
+    Sse::new(stream::once(event)).into_response()
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
@@ -0,0 +1,120 @@
+//! The TLS handshake the mock notary attests to.
+//!
+//! Targets are reached over plain HTTP, so there is no real handshake to notarize.
+//! Instead a handshake is made up for every session: the server certificate is issued
+//! for the target host by a test certificate authority, and signs an ephemeral P-256
+//! key exchange like a TLS 1.2 ECDHE server would. Verifiers that trust
+//! [`root_certificate`] accept the resulting server identity.
+
+use std::sync::OnceLock;
+
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::{DerSignature, SigningKey};
+use p256::pkcs8::EncodePrivateKey;
+use rcgen::{BasicConstraints, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa, KeyPair};
+use tls_core::cert::ServerCertDetails;
+use tls_core::handshake::HandshakeData;
+use tls_core::ke::ServerKxDetails;
+use tls_core::key::{Certificate, PublicKey};
+use tls_core::msgs::enums::{NamedGroup, SignatureScheme};
+use tls_core::msgs::handshake::{DigitallySignedStruct, Random};
+
+/// `ECCurveType::named_curve` of a `ServerECDHParams` structure.
+const NAMED_CURVE: u8 = 3;
+
+/// The `NamedGroup` code of secp256r1.
+const SECP256R1: [u8; 2] = [0x00, 0x17];
+
+/// The test certificate authority, created once per process.
+struct Authority {
+    certificate: rcgen::Certificate,
+    key: KeyPair,
+}
+
+fn authority() -> &'static Authority {
+    static AUTHORITY: OnceLock<Authority> = OnceLock::new();
+
+    AUTHORITY.get_or_init(|| {
+        let key = KeyPair::generate().expect("failed to generate the test CA key");
+        let mut params = CertificateParams::new(Vec::new()).expect("valid CA parameters");
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "Verity Mock Prover Test CA");
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let certificate = params
+            .self_signed(&key)
+            .expect("failed to self-sign the test CA");
+
+        Authority { certificate, key }
+    })
+}
+
+/// The DER encoded certificate of the test certificate authority, which issues the
+/// server certificates of every session the mock notary attests to.
+pub fn root_certificate() -> Vec<u8> {
+    authority().certificate.der().to_vec()
+}
+
+/// Makes up the handshake of a TLS session with `server_name`, returning it along with
+/// the ephemeral public key of the server.
+pub(crate) fn handshake(server_name: &str) -> Result<(HandshakeData, PublicKey), String> {
+    let authority = authority();
+
+    // The server certificate key, also held as a P-256 key to sign the key exchange
+    let server_key = SigningKey::random(&mut rand::thread_rng());
+    let server_key_der = server_key.to_pkcs8_der().map_err(|e| e.to_string())?;
+    let server_key_pair =
+        KeyPair::try_from(server_key_der.as_bytes()).map_err(|e| e.to_string())?;
+
+    let mut params =
+        CertificateParams::new(vec![server_name.to_string()]).map_err(|e| e.to_string())?;
+    params
+        .distinguished_name
+        .push(DnType::CommonName, server_name);
+    params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+    let server_certificate = params
+        .signed_by(&server_key_pair, &authority.certificate, &authority.key)
+        .map_err(|e| e.to_string())?;
+
+    // ServerECDHParams of an ephemeral secp256r1 key
+    let ephemeral_key = p256::SecretKey::random(&mut rand::thread_rng());
+    let ephemeral_point = ephemeral_key.public_key().to_sec1_bytes();
+    let mut kx_params = vec![NAMED_CURVE];
+    kx_params.extend_from_slice(&SECP256R1);
+    kx_params.push(ephemeral_point.len() as u8);
+    kx_params.extend_from_slice(&ephemeral_point);
+
+    // The server signs the randoms and its key exchange parameters
+    let client_random: [u8; 32] = rand::random();
+    let server_random: [u8; 32] = rand::random();
+    let mut message = Vec::new();
+    message.extend_from_slice(&client_random);
+    message.extend_from_slice(&server_random);
+    message.extend_from_slice(&kx_params);
+    let signature: DerSignature = server_key.sign(&message);
+
+    let handshake = HandshakeData::new(
+        ServerCertDetails::new(
+            vec![
+                Certificate(server_certificate.der().to_vec()),
+                Certificate(authority.certificate.der().to_vec()),
+            ],
+            Vec::new(),
+            None,
+        ),
+        ServerKxDetails::new(
+            kx_params,
+            DigitallySignedStruct::new(
+                SignatureScheme::ECDSA_NISTP256_SHA256,
+                signature.as_bytes().to_vec(),
+            ),
+        ),
+        Random(client_random),
+        Random(server_random),
+    );
+
+    Ok((
+        handshake,
+        PublicKey::new(NamedGroup::secp256r1, &ephemeral_point),
+    ))
+}
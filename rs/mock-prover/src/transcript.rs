@@ -0,0 +1,301 @@
+use std::ops::Range;
+
+use regex::bytes::Regex;
+use verity_client::redaction::{Redaction, RedactionTarget};
+
+/// One side of a plaintext HTTP/1.1 exchange, as the notary would see it inside TLS.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct HttpMessage {
+    pub(crate) bytes: Vec<u8>,
+    /// Byte ranges of every header value, by lowercase header name.
+    headers: Vec<(String, Range<usize>)>,
+    /// Offset at which the body starts.
+    body_start: usize,
+}
+
+impl HttpMessage {
+    /// Serializes a message from its start line, headers and body.
+    pub(crate) fn new<'a>(
+        start_line: &str,
+        headers: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+        body: &[u8],
+    ) -> Self {
+        let mut bytes = format!("{}\r\n", start_line).into_bytes();
+        let mut ranges = Vec::new();
+
+        for (name, value) in headers {
+            bytes.extend_from_slice(name.as_bytes());
+            bytes.extend_from_slice(b": ");
+            ranges.push((
+                name.to_ascii_lowercase(),
+                bytes.len()..bytes.len() + value.len(),
+            ));
+            bytes.extend_from_slice(value);
+            bytes.extend_from_slice(b"\r\n");
+        }
+
+        bytes.extend_from_slice(b"\r\n");
+        let body_start = bytes.len();
+        bytes.extend_from_slice(body);
+
+        Self {
+            bytes,
+            headers: ranges,
+            body_start,
+        }
+    }
+
+    /// Returns the ranges of this message that remain visible after applying the
+    /// redactions aimed at `target`.
+    pub(crate) fn revealed(
+        &self,
+        redactions: &[Redaction],
+        target: RedactionTarget,
+    ) -> Vec<Range<usize>> {
+        let mut hidden: Vec<Range<usize>> = redactions
+            .iter()
+            .filter(|redaction| redaction_target(redaction) == target)
+            .flat_map(|redaction| self.hidden_by(redaction))
+            .filter(|range| !range.is_empty())
+            .collect();
+        hidden.sort_by_key(|range| range.start);
+
+        let mut revealed = Vec::new();
+        let mut cursor = 0;
+        for range in hidden {
+            if range.start > cursor {
+                revealed.push(cursor..range.start);
+            }
+            cursor = cursor.max(range.end);
+        }
+        if cursor < self.bytes.len() {
+            revealed.push(cursor..self.bytes.len());
+        }
+
+        revealed
+    }
+
+    fn hidden_by(&self, redaction: &Redaction) -> Vec<Range<usize>> {
+        let body = &self.bytes[self.body_start..];
+        let in_body =
+            |range: Range<usize>| self.body_start + range.start..self.body_start + range.end;
+
+        match redaction {
+            Redaction::Header { name, .. } => self
+                .headers
+                .iter()
+                .filter(|(header, _)| header.eq_ignore_ascii_case(name))
+                .map(|(_, range)| range.clone())
+                .collect(),
+            Redaction::JsonPath { path, .. } => {
+                json_value(body, path).map(in_body).into_iter().collect()
+            }
+            Redaction::Regex { pattern, .. } => Regex::new(pattern)
+                .map(|regex| {
+                    regex
+                        .find_iter(body)
+                        .map(|found| in_body(found.range()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            Redaction::Range { range, .. } => {
+                let clamped = range.start.min(self.bytes.len())..range.end.min(self.bytes.len());
+                std::iter::once(clamped).collect()
+            }
+        }
+    }
+}
+
+/// Finds the bytes of the value at a dot-separated JSON path, where numeric segments
+/// index into arrays. String values include their quotes, and keys are compared
+/// without unescaping.
+fn json_value(body: &[u8], path: &str) -> Option<Range<usize>> {
+    let mut start = skip_whitespace(body, 0);
+    for segment in path.split('.') {
+        start = match body.get(start)? {
+            b'{' => object_member(body, start, segment)?,
+            b'[' => array_element(body, start, segment.parse().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(start..value_end(body, start)?)
+}
+
+/// Returns the offset of the value of member `key` of the object opening at `open`.
+fn object_member(body: &[u8], open: usize, key: &str) -> Option<usize> {
+    let mut pos = skip_whitespace(body, open + 1);
+    if body.get(pos) == Some(&b'}') {
+        return None;
+    }
+    loop {
+        let key_end = value_end(body, pos)?;
+        let name = body.get(pos + 1..key_end - 1)?;
+        pos = skip_whitespace(body, key_end);
+        if body.get(pos) != Some(&b':') {
+            return None;
+        }
+        let value = skip_whitespace(body, pos + 1);
+        if name == key.as_bytes() {
+            return Some(value);
+        }
+        pos = skip_whitespace(body, value_end(body, value)?);
+        if body.get(pos) != Some(&b',') {
+            return None;
+        }
+        pos = skip_whitespace(body, pos + 1);
+    }
+}
+
+/// Returns the offset of element `index` of the array opening at `open`.
+fn array_element(body: &[u8], open: usize, index: usize) -> Option<usize> {
+    let mut pos = skip_whitespace(body, open + 1);
+    if body.get(pos) == Some(&b']') {
+        return None;
+    }
+    for _ in 0..index {
+        pos = skip_whitespace(body, value_end(body, pos)?);
+        if body.get(pos) != Some(&b',') {
+            return None;
+        }
+        pos = skip_whitespace(body, pos + 1);
+    }
+    Some(pos)
+}
+
+/// Returns the offset just past the JSON value starting at `start`.
+fn value_end(body: &[u8], start: usize) -> Option<usize> {
+    match body.get(start)? {
+        b'"' => {
+            let mut pos = start + 1;
+            loop {
+                match body.get(pos)? {
+                    b'\\' => pos += 2,
+                    b'"' => return Some(pos + 1),
+                    _ => pos += 1,
+                }
+            }
+        }
+        b'{' | b'[' => {
+            let mut depth = 0usize;
+            let mut pos = start;
+            loop {
+                match body.get(pos)? {
+                    b'"' => {
+                        pos = value_end(body, pos)?;
+                        continue;
+                    }
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(pos + 1);
+                        }
+                    }
+                    _ => {}
+                }
+                pos += 1;
+            }
+        }
+        _ => {
+            let len = body[start..]
+                .iter()
+                .position(|b| matches!(b, b',' | b'}' | b']') || b.is_ascii_whitespace())
+                .unwrap_or(body.len() - start);
+            (len > 0).then_some(start + len)
+        }
+    }
+}
+
+fn skip_whitespace(body: &[u8], pos: usize) -> usize {
+    pos + body.get(pos..).map_or(0, |rest| {
+        rest.iter().take_while(|b| b.is_ascii_whitespace()).count()
+    })
+}
+
+fn redaction_target(redaction: &Redaction) -> RedactionTarget {
+    match redaction {
+        Redaction::Header { target, .. }
+        | Redaction::JsonPath { target, .. }
+        | Redaction::Regex { target, .. }
+        | Redaction::Range { target, .. } => *target,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response() -> HttpMessage {
+        HttpMessage::new(
+            "HTTP/1.1 200 OK",
+            [
+                ("content-type", b"application/json".as_slice()),
+                ("x-api-key", b"secret".as_slice()),
+            ],
+            br#"{"id":1,"data":{"token":"abc"},"token":"zzz","items":[7, {"pin":"12"}]}"#,
+        )
+    }
+
+    fn hidden(message: &HttpMessage, redaction: Redaction) -> Vec<String> {
+        let revealed = message.revealed(&[redaction], RedactionTarget::Response);
+        let mut visible = vec![b'X'; message.bytes.len()];
+        for range in revealed {
+            visible[range.clone()].copy_from_slice(&message.bytes[range]);
+        }
+        String::from_utf8(visible)
+            .unwrap()
+            .split(|c| c != 'X')
+            .filter(|part| !part.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn serializes_http_messages() {
+        let message = response();
+        assert_eq!(
+            String::from_utf8(message.bytes).unwrap(),
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\nx-api-key: secret\r\n\r\n{\"id\":1,\"data\":{\"token\":\"abc\"},\"token\":\"zzz\",\"items\":[7, {\"pin\":\"12\"}]}"
+        );
+    }
+
+    #[test]
+    fn redacts_headers_json_values_and_regex_matches() {
+        let message = response();
+
+        assert_eq!(
+            hidden(&message, Redaction::response_header("X-Api-Key")),
+            ["XXXXXX"]
+        );
+        assert_eq!(
+            hidden(&message, Redaction::response_json("data.token")),
+            ["XXXXX"]
+        );
+        assert_eq!(
+            hidden(&message, Redaction::response_json("items.1.pin")),
+            ["XXXX"]
+        );
+        assert!(hidden(&message, Redaction::response_json("items.2")).is_empty());
+        assert!(hidden(&message, Redaction::response_json("pin")).is_empty());
+        assert_eq!(
+            hidden(&message, Redaction::response_regex("[0-9]+")),
+            ["X", "X", "XX"]
+        );
+        assert_eq!(
+            hidden(&message, Redaction::response_range(0..8)),
+            ["XXXXXXXX"]
+        );
+    }
+
+    #[test]
+    fn ignores_redactions_for_the_other_direction() {
+        let message = response();
+        let revealed = message.revealed(
+            &[Redaction::request_header("x-api-key")],
+            RedactionTarget::Response,
+        );
+
+        assert_eq!(revealed.len(), 1);
+        assert_eq!(revealed[0], 0..message.bytes.len());
+    }
+}
@@ -0,0 +1,301 @@
+use axum::http::HeaderMap;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use verity_client::client::{VerityClient, VerityClientConfig};
+use verity_client::{ProofEncoding, ProofTransport, ProverAuth, Redaction};
+use verity_mock_prover::{root_certificate, MockProver, NOTARY_PUBLIC_KEY};
+use verity_verify_tls::{no_session, VerifyTlsError};
+
+async fn spawn_target() -> (String, JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let app = Router::new()
+        .route(
+            "/account",
+            get(|| async { Json(json!({ "id": 7, "token": "s3cr3t", "balance": 42 })) }),
+        )
+        .route(
+            "/headers",
+            get(|headers: HeaderMap| async move {
+                let headers: Vec<String> = headers
+                    .iter()
+                    .map(|(name, value)| format!("{}: {}", name, value.to_str().unwrap()))
+                    .collect();
+                Json(headers)
+            }),
+        )
+        .route(
+            "/echo",
+            post(|headers: HeaderMap, body: String| async move {
+                let key = headers
+                    .get("x-api-key")
+                    .map(|value| value.to_str().unwrap().to_string());
+                Json(json!({ "key": key, "body": body }))
+            }),
+        );
+
+    let handle = tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service())
+            .await
+            .unwrap();
+    });
+
+    (format!("http://{}", addr), handle)
+}
+
+/// Verifies a session proof against the mock notary and its test certificate authority,
+/// returning the revealed `(sent, recv)` transcripts.
+fn verify(proof: &str) -> (String, String) {
+    let (recv, sent) = verity_verify_tls::verify_proof_bytes_with_roots(
+        proof,
+        NOTARY_PUBLIC_KEY,
+        &[root_certificate()],
+    )
+    .unwrap()
+    .into_strings()
+    .unwrap();
+    (sent, recv)
+}
+
+#[tokio::test]
+async fn proves_a_request_end_to_end() {
+    let prover = MockProver::start().await.unwrap();
+    let (target, _server) = spawn_target().await;
+
+    let client = VerityClient::new(VerityClientConfig::new(prover.url()));
+    let response = client
+        .get(format!("{}/account", target))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        response.notary_pub_key.replace("\\n", "\n"),
+        NOTARY_PUBLIC_KEY
+    );
+    let body: Value = response.subject.json().await.unwrap();
+    assert_eq!(body["balance"], 42);
+
+    let (sent, recv) = verify(&response.proof);
+    assert!(sent.starts_with("GET /account HTTP/1.1\r\n"));
+    assert!(recv.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(recv.ends_with(r#"{"balance":42,"id":7,"token":"s3cr3t"}"#));
+}
+
+#[tokio::test]
+async fn session_proofs_are_signed_and_name_the_target() {
+    let prover = MockProver::start().await.unwrap();
+    let (target, _server) = spawn_target().await;
+
+    let client = VerityClient::new(VerityClientConfig::new(prover.url()));
+    let response = client
+        .get(format!("{}/account", target))
+        .send()
+        .await
+        .unwrap();
+
+    let roots = [prover.root_certificate()];
+    let transcripts = verity_verify_tls::verify_proof_bytes_with_roots(
+        &response.proof,
+        NOTARY_PUBLIC_KEY,
+        &roots,
+    )
+    .unwrap();
+    assert_eq!(transcripts.server_name.as_deref(), Some("localhost"));
+    let exchanges = transcripts.http_exchanges().unwrap();
+    assert_eq!(exchanges[0].server_name.as_deref(), Some("localhost"));
+
+    // Another notary key does not verify the signature
+    let other_key = "-----BEGIN PUBLIC KEY-----
+MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAEOBMK9uFRjEViM1Zb53tGljs8+jmr
+D2bBzGS8BkfUlj9IAV4NxoCOpcqsJdp1fJbyYe47oQ66o0jS+egMMHQfQg==
+-----END PUBLIC KEY-----
+";
+    let result =
+        verity_verify_tls::verify_proof_bytes_with_roots(&response.proof, other_key, &roots);
+    assert!(matches!(result, Err(VerifyTlsError::Signature(_))));
+
+    // The test certificate authority is not one of the `webpki-roots`
+    let result = verity_verify_tls::verify_proof(&response.proof, NOTARY_PUBLIC_KEY);
+    assert!(matches!(result, Err(VerifyTlsError::Certificate(_))));
+
+    // Substring-only verification accepts the session proof as it is
+    let (_, recv) = no_session::verify_proof(&response.proof).unwrap();
+    assert!(recv.ends_with(r#"{"balance":42,"id":7,"token":"s3cr3t"}"#));
+}
+
+#[tokio::test]
+async fn proofs_are_delivered_over_every_transport() {
+    let prover = MockProver::start().await.unwrap();
+    let (target, _server) = spawn_target().await;
+
+    for transport in [
+        ProofTransport::Sse,
+        ProofTransport::WebSocket,
+        ProofTransport::LongPolling,
+    ] {
+        let client = VerityClient::new(
+            VerityClientConfig::new(prover.url()).with_proof_transport(transport),
+        );
+        let response = client
+            .get(format!("{}/account", target))
+            .send()
+            .await
+            .unwrap();
+
+        let (sent, recv) = verify(&response.proof);
+        assert!(
+            sent.starts_with("GET /account HTTP/1.1\r\n"),
+            "{:?}",
+            transport
+        );
+        assert!(recv.ends_with(r#"{"balance":42,"id":7,"token":"s3cr3t"}"#));
+    }
+}
+
+#[tokio::test]
+async fn compressed_proofs_verify() {
+    let prover = MockProver::start().await.unwrap();
+    let (target, _server) = spawn_target().await;
+
+    let client = VerityClient::new(
+        VerityClientConfig::new(prover.url()).with_compressed_proofs(ProofEncoding::Zstd),
+    );
+    let response = client
+        .get(format!("{}/account", target))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.proof.starts_with("zstd:"));
+
+    let bundle = response.to_bundle();
+    let (_, recv) = verify(&bundle.proof_json().unwrap());
+    assert!(recv.ends_with(r#"{"balance":42,"id":7,"token":"s3cr3t"}"#));
+}
+
+#[tokio::test]
+async fn redacted_values_are_hidden_from_the_proof() {
+    let prover = MockProver::start().await.unwrap();
+    let (target, _server) = spawn_target().await;
+
+    let client = VerityClient::new(VerityClientConfig::new(prover.url()));
+    let response = client
+        .post(format!("{}/echo", target))
+        .header("x-api-key", "top-secret")
+        .body("hello")
+        .redact(Redaction::request_header("x-api-key"))
+        .redact(Redaction::response_json("key"))
+        .send()
+        .await
+        .unwrap();
+
+    let (sent, recv) = verify(&response.proof);
+    assert!(sent.contains("x-api-key: XXXXXXXXXX\r\n"));
+    assert!(sent.ends_with("\r\n\r\nhello"));
+    assert!(recv.ends_with(r#"{"body":"hello","key":XXXXXXXXXXXX}"#));
+}
+
+#[tokio::test]
+async fn proofs_parse_into_http_exchanges() {
+    let prover = MockProver::start().await.unwrap();
+    let (target, _server) = spawn_target().await;
+
+    let client = VerityClient::new(VerityClientConfig::new(prover.url()));
+    let response = client
+        .post(format!("{}/echo", target))
+        .header("x-api-key", "top-secret")
+        .body("hello")
+        .redact(Redaction::request_header("x-api-key"))
+        .send()
+        .await
+        .unwrap();
+
+    let exchanges = no_session::verify_http_proof(&response.proof).unwrap();
+    assert_eq!(exchanges.len(), 1);
+
+    let request = &exchanges[0].request;
+    assert_eq!(request.method, "POST");
+    assert!(request.url.ends_with("/echo"));
+    assert!(request.header("x-api-key").unwrap().is_redacted());
+    assert_eq!(request.body.data, b"hello");
+
+    let response = &exchanges[0].response;
+    assert_eq!(response.status, 200);
+    assert!(!response.body.is_redacted());
+    assert_eq!(
+        response.body.text(),
+        r#"{"body":"hello","key":"top-secret"}"#
+    );
+}
+
+#[tokio::test]
+async fn prover_credentials_are_not_forwarded_or_notarized() {
+    let prover = MockProver::start().await.unwrap();
+    let (target, _server) = spawn_target().await;
+
+    for auth in [
+        ProverAuth::header("x-api-key", "gateway-secret").unwrap(),
+        ProverAuth::bearer("gateway-secret"),
+    ] {
+        let client =
+            VerityClient::new(VerityClientConfig::new(prover.url()).with_prover_auth(auth));
+        let response = client
+            .get(format!("{}/headers", target))
+            .send()
+            .await
+            .unwrap();
+
+        let received: Vec<String> = response.subject.json().await.unwrap();
+        assert!(!received.is_empty());
+        assert!(received
+            .iter()
+            .all(|header| !header.contains("gateway-secret")));
+
+        let (sent, recv) = verify(&response.proof);
+        assert!(sent.starts_with("GET /headers HTTP/1.1\r\n"));
+        assert!(!sent.contains("gateway-secret"));
+        assert!(!recv.contains("gateway-secret"));
+    }
+}
+
+#[tokio::test]
+async fn failed_requests_are_not_proven_by_default() {
+    let prover = MockProver::start().await.unwrap();
+    let (target, _server) = spawn_target().await;
+
+    let client = VerityClient::new(VerityClientConfig::new(prover.url()));
+    let response = client
+        .get(format!("{}/missing", target))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.subject.status(), 404);
+    assert!(response.proof.is_empty());
+
+    let response = client
+        .get(format!("{}/missing", target))
+        .prove_failed_request()
+        .send()
+        .await
+        .unwrap();
+
+    let (_, recv) = verify(&response.proof);
+    assert!(recv.starts_with("HTTP/1.1 404 Not Found\r\n"));
+}
+
+#[tokio::test]
+async fn serves_notary_information() {
+    let prover = MockProver::start().await.unwrap();
+
+    let client = VerityClient::new(VerityClientConfig::new(prover.url()));
+    let info = client.get_notary_info().await.unwrap();
+
+    assert_eq!(info.public_key, prover.notary_public_key());
+    assert_eq!(info.version, verity_mock_prover::NOTARY_VERSION);
+}
@@ -0,0 +1,258 @@
+//! Compression of proofs.
+//!
+//! Proofs of large responses are large JSON documents, which compress well. A
+//! compressed proof exists in two forms:
+//!
+//! - as raw bytes, which [`decompress_proof`] recognizes by their gzip or zstd magic
+//!   number, and
+//! - as text, the encoding name and the base64 encoded bytes separated by a colon, such
+//!   as `zstd:KLUv/...`. This form travels in the `pubkey|proof` message of the prover
+//!   and in [`ProofBundle::proof`](crate::ProofBundle::proof).
+//!
+//! Both codecs are implemented in pure Rust, so decompression also works in canisters
+//! and zkVM guests.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{Read, Write};
+use std::str::FromStr;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use ruzstd::decoding::StreamingDecoder;
+use ruzstd::encoding::{compress_to_vec, CompressionLevel};
+
+use crate::BundleError;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// The largest proof decompression produces. Larger proofs are rejected, so a small
+/// compressed payload cannot expand without bound.
+pub const MAX_DECOMPRESSED_SIZE: usize = 64 * 1024 * 1024;
+
+/// A compression a proof can be encoded with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProofEncoding {
+    Gzip,
+    Zstd,
+}
+
+impl ProofEncoding {
+    /// Every supported encoding, most preferred first.
+    pub const ALL: [ProofEncoding; 2] = [ProofEncoding::Zstd, ProofEncoding::Gzip];
+
+    /// The name of the encoding, as used in the text form and the `T-PROOF-ENCODING`
+    /// header.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProofEncoding::Gzip => "gzip",
+            ProofEncoding::Zstd => "zstd",
+        }
+    }
+
+    /// Compresses `proof` into raw bytes.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the compressor fails.
+    pub fn compress(self, proof: &str) -> Result<Vec<u8>, BundleError> {
+        match self {
+            ProofEncoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder
+                    .write_all(proof.as_bytes())
+                    .map_err(compression_error)?;
+                encoder.finish().map_err(compression_error)
+            }
+            ProofEncoding::Zstd => Ok(compress_to_vec(proof.as_bytes(), CompressionLevel::Fastest)),
+        }
+    }
+
+    /// Decompresses raw bytes compressed with this encoding.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the bytes cannot be decompressed, decompress to more than
+    /// [`MAX_DECOMPRESSED_SIZE`] bytes, or are not UTF-8.
+    pub fn decompress(self, bytes: &[u8]) -> Result<String, BundleError> {
+        self.decompress_with_limit(bytes, MAX_DECOMPRESSED_SIZE)
+    }
+
+    /// Decompresses raw bytes like `decompress`, failing once the output exceeds
+    /// `limit` bytes.
+    fn decompress_with_limit(self, bytes: &[u8], limit: usize) -> Result<String, BundleError> {
+        match self {
+            ProofEncoding::Gzip => read_bounded(GzDecoder::new(bytes), limit),
+            ProofEncoding::Zstd => {
+                let mut source = bytes;
+                read_bounded(
+                    StreamingDecoder::new(&mut source).map_err(compression_error)?,
+                    limit,
+                )
+            }
+        }
+    }
+
+    /// Compresses `proof` into its text form, such as `zstd:KLUv/...`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the compressor fails.
+    pub fn encode(self, proof: &str) -> Result<String, BundleError> {
+        Ok(format!(
+            "{}:{}",
+            self,
+            STANDARD.encode(self.compress(proof)?)
+        ))
+    }
+}
+
+impl fmt::Display for ProofEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ProofEncoding {
+    type Err = BundleError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.trim() {
+            name if name.eq_ignore_ascii_case("gzip") => Ok(ProofEncoding::Gzip),
+            name if name.eq_ignore_ascii_case("zstd") => Ok(ProofEncoding::Zstd),
+            name => Err(BundleError::Compression(format!(
+                "unknown proof encoding {}",
+                name
+            ))),
+        }
+    }
+}
+
+/// The encoding of a proof in text form, or `None` if the proof is not compressed.
+pub fn proof_encoding(proof: &str) -> Option<ProofEncoding> {
+    let (name, _) = proof.split_once(':')?;
+    ProofEncoding::ALL
+        .into_iter()
+        .find(|encoding| encoding.as_str() == name)
+}
+
+/// Returns the JSON of a proof in text form, decompressing it if needed. Uncompressed
+/// proofs are returned as is.
+///
+/// # Errors
+///
+/// This method fails if the proof is compressed but cannot be decompressed.
+pub fn decode_proof(proof: &str) -> Result<Cow<'_, str>, BundleError> {
+    match (proof_encoding(proof), proof.split_once(':')) {
+        (Some(encoding), Some((_, data))) => {
+            let bytes = STANDARD.decode(data).map_err(compression_error)?;
+            Ok(Cow::Owned(encoding.decompress(&bytes)?))
+        }
+        _ => Ok(Cow::Borrowed(proof)),
+    }
+}
+
+/// Returns the JSON of a proof in raw bytes, decompressing gzip or zstd bytes as
+/// recognized by their magic number. Other bytes are read as an uncompressed proof.
+///
+/// # Errors
+///
+/// This method fails if the bytes cannot be decompressed, or are not UTF-8.
+pub fn decompress_proof(bytes: &[u8]) -> Result<String, BundleError> {
+    if bytes.starts_with(&GZIP_MAGIC) {
+        ProofEncoding::Gzip.decompress(bytes)
+    } else if bytes.starts_with(&ZSTD_MAGIC) {
+        ProofEncoding::Zstd.decompress(bytes)
+    } else {
+        String::from_utf8(bytes.to_vec()).map_err(compression_error)
+    }
+}
+
+/// Reads a decompressed proof from `decoder`, failing once it exceeds `limit` bytes.
+fn read_bounded(decoder: impl Read, limit: usize) -> Result<String, BundleError> {
+    let mut proof = String::new();
+    decoder
+        .take(limit as u64 + 1)
+        .read_to_string(&mut proof)
+        .map_err(compression_error)?;
+
+    if proof.len() > limit {
+        return Err(BundleError::Compression(format!(
+            "decompressed proof exceeds {} bytes",
+            limit
+        )));
+    }
+
+    Ok(proof)
+}
+
+fn compression_error(err: impl fmt::Display) -> BundleError {
+    BundleError::Compression(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROOF: &str =
+        r#"{"session":{"header":[1,2,3]},"substrings":{"openings":[0,0,0,0,0,0,0,0]}}"#;
+
+    #[test]
+    fn round_trips_every_encoding() {
+        for encoding in ProofEncoding::ALL {
+            let bytes = encoding.compress(PROOF).unwrap();
+            assert_eq!(decompress_proof(&bytes).unwrap(), PROOF);
+
+            let text = encoding.encode(PROOF).unwrap();
+            assert!(text.starts_with(&format!("{}:", encoding)));
+            assert_eq!(proof_encoding(&text), Some(encoding));
+            assert_eq!(decode_proof(&text).unwrap(), PROOF);
+        }
+    }
+
+    #[test]
+    fn passes_uncompressed_proofs_through() {
+        assert_eq!(proof_encoding(PROOF), None);
+        assert!(matches!(decode_proof(PROOF).unwrap(), Cow::Borrowed(PROOF)));
+        assert_eq!(decompress_proof(PROOF.as_bytes()).unwrap(), PROOF);
+    }
+
+    #[test]
+    fn rejects_corrupt_proofs() {
+        assert!(decode_proof("zstd:not base64!").is_err());
+        assert!(decode_proof("gzip:AAAA").is_err());
+        assert!(decompress_proof(&[0x1f, 0x8b, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn rejects_oversized_proofs() {
+        let limit = PROOF.len() - 1;
+        for encoding in ProofEncoding::ALL {
+            let bytes = encoding.compress(PROOF).unwrap();
+            assert!(matches!(
+                encoding.decompress_with_limit(&bytes, limit),
+                Err(BundleError::Compression(message)) if message.contains("exceeds")
+            ));
+            assert_eq!(
+                encoding.decompress_with_limit(&bytes, PROOF.len()).unwrap(),
+                PROOF
+            );
+        }
+    }
+
+    #[test]
+    fn parses_encoding_names() {
+        assert_eq!(
+            "GZIP".parse::<ProofEncoding>().unwrap(),
+            ProofEncoding::Gzip
+        );
+        assert_eq!(
+            " zstd".parse::<ProofEncoding>().unwrap(),
+            ProofEncoding::Zstd
+        );
+        assert!("br".parse::<ProofEncoding>().is_err());
+    }
+}
@@ -0,0 +1,302 @@
+//! A self-describing container for Verity TLS proofs.
+//!
+//! A proof on its own does not say which request it proves or which notary signed it.
+//! A [`ProofBundle`] stores the proof together with the notary key, the request it was
+//! produced for and the redaction rules applied to it, so stored proofs keep their
+//! context. Bundles are versioned and can be encoded as JSON or as compact binary.
+//!
+//! With the `compression` feature, proofs can be kept gzip or zstd compressed, see
+//! [`compression`].
+
+#[cfg(feature = "compression")]
+pub mod compression;
+
+#[cfg(feature = "compression")]
+use std::borrow::Cow;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The bundle format version written by this crate.
+pub const FORMAT_VERSION: u16 = 1;
+
+/// Errors returned when encoding or decoding a proof bundle.
+#[derive(Debug, Error)]
+pub enum BundleError {
+    /// The bundle is not valid JSON, or does not describe a bundle.
+    #[error("invalid JSON proof bundle: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The bundle is not a valid binary encoding of a bundle.
+    #[error("invalid binary proof bundle: {0}")]
+    Binary(#[from] bincode::Error),
+
+    /// The bundle was written by a newer version of the format.
+    #[error("unsupported proof bundle version {0}, expected at most {FORMAT_VERSION}")]
+    UnsupportedVersion(u16),
+
+    /// A proof could not be compressed or decompressed.
+    #[error("proof compression failed: {0}")]
+    Compression(String),
+}
+
+/// The information a prover publishes about its notary on `/notaryinfo`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotaryInformation {
+    pub version: String,
+    pub public_key: String,
+    pub git_commit_hash: String,
+    pub git_commit_timestamp: String,
+}
+
+/// A proof along with the request it proves and the notary that signed it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofBundle {
+    /// The format version, always the first field so it can be read before the rest.
+    pub version: u16,
+    /// The proof, as delivered by the prover. May be compressed, see
+    /// [`ProofBundle::proof_json`].
+    pub proof: String,
+    /// The PEM encoded public key of the notary that signed the proof.
+    pub notary_pub_key: String,
+    /// The notary information published by the prover, if it was fetched.
+    pub notary_info: Option<NotaryInformation>,
+    /// The `T-REQUEST-ID` the request was sent to the prover with.
+    pub request_id: String,
+    /// The method of the proven request.
+    pub method: String,
+    /// The target URL of the proven request.
+    pub url: String,
+    /// When the proof was received, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The redaction rules applied to the proof, in the `T-REDACTED` header format.
+    pub redactions: Vec<String>,
+}
+
+impl ProofBundle {
+    /// Creates a bundle for a proof, timestamped now. The request details are empty.
+    pub fn new(proof: impl Into<String>, notary_pub_key: impl Into<String>) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            proof: proof.into(),
+            notary_pub_key: notary_pub_key.into(),
+            notary_info: None,
+            request_id: String::new(),
+            method: String::new(),
+            url: String::new(),
+            timestamp: unix_timestamp(SystemTime::now()),
+            redactions: Vec::new(),
+        }
+    }
+
+    /// Set the request the proof was produced for.
+    pub fn with_request(
+        mut self,
+        request_id: impl Into<String>,
+        method: impl Into<String>,
+        url: impl Into<String>,
+    ) -> Self {
+        self.request_id = request_id.into();
+        self.method = method.into();
+        self.url = url.into();
+        self
+    }
+
+    /// Set the notary information published by the prover.
+    pub fn with_notary_info(mut self, notary_info: NotaryInformation) -> Self {
+        self.notary_info = Some(notary_info);
+        self
+    }
+
+    /// Set when the proof was received.
+    pub fn with_timestamp(mut self, timestamp: SystemTime) -> Self {
+        self.timestamp = unix_timestamp(timestamp);
+        self
+    }
+
+    /// Set the redaction rules applied to the proof.
+    pub fn with_redactions(mut self, redactions: Vec<String>) -> Self {
+        self.redactions = redactions;
+        self
+    }
+
+    /// Compresses the proof with `encoding`, unless it is already compressed with it.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the proof cannot be decompressed or compressed.
+    #[cfg(feature = "compression")]
+    pub fn compressed(mut self, encoding: compression::ProofEncoding) -> Result<Self, BundleError> {
+        if compression::proof_encoding(&self.proof) != Some(encoding) {
+            self.proof = encoding.encode(&compression::decode_proof(&self.proof)?)?;
+        }
+        Ok(self)
+    }
+
+    /// The JSON of the proof, decompressed if the proof is compressed.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the proof is compressed but cannot be decompressed.
+    #[cfg(feature = "compression")]
+    pub fn proof_json(&self) -> Result<Cow<'_, str>, BundleError> {
+        compression::decode_proof(&self.proof)
+    }
+
+    /// Encodes the bundle as pretty printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the bundle cannot be serialized.
+    pub fn to_json(&self) -> Result<String, BundleError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Decodes a bundle from JSON.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if `json` is not a bundle, or if its version is unsupported.
+    pub fn from_json(json: &str) -> Result<Self, BundleError> {
+        #[derive(Deserialize)]
+        struct Versioned {
+            version: u16,
+        }
+
+        let Versioned { version } = serde_json::from_str(json)?;
+        check_version(version)?;
+
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Encodes the bundle in its compact binary form.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the bundle cannot be serialized.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, BundleError> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Decodes a bundle from its compact binary form.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if `bytes` is not a bundle, or if its version is unsupported.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BundleError> {
+        let version: u16 = bincode::deserialize(bytes)?;
+        check_version(version)?;
+
+        Ok(bincode::deserialize(bytes)?)
+    }
+
+    /// Decodes a bundle from either encoding. Input starting with `{`, after any
+    /// whitespace, is read as JSON.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if `bytes` is not a bundle, or if its version is unsupported.
+    pub fn decode(bytes: &[u8]) -> Result<Self, BundleError> {
+        match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{') => {
+                let json = std::str::from_utf8(bytes)
+                    .map_err(|e| BundleError::Json(serde::de::Error::custom(e)))?;
+                Self::from_json(json)
+            }
+            _ => Self::from_bytes(bytes),
+        }
+    }
+}
+
+fn check_version(version: u16) -> Result<(), BundleError> {
+    if version > FORMAT_VERSION {
+        return Err(BundleError::UnsupportedVersion(version));
+    }
+
+    Ok(())
+}
+
+fn unix_timestamp(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle() -> ProofBundle {
+        ProofBundle::new("proof", "-----BEGIN PUBLIC KEY-----")
+            .with_request(
+                "6c1f2b0e-1d44-4d38-9a43-3d2f4a3c8f10",
+                "GET",
+                "https://example.com",
+            )
+            .with_notary_info(NotaryInformation {
+                version: "0.1.0".to_string(),
+                public_key: "-----BEGIN PUBLIC KEY-----".to_string(),
+                git_commit_hash: "abc".to_string(),
+                git_commit_timestamp: "2024-01-01".to_string(),
+            })
+            .with_redactions(vec!["res:body:token".to_string()])
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let bundle = bundle();
+        let json = bundle.to_json().unwrap();
+
+        assert!(json.contains("\"notaryPubKey\""));
+        assert_eq!(ProofBundle::from_json(&json).unwrap(), bundle);
+        assert_eq!(ProofBundle::decode(json.as_bytes()).unwrap(), bundle);
+    }
+
+    #[test]
+    fn round_trips_bytes() {
+        let bundle = bundle();
+        let bytes = bundle.to_bytes().unwrap();
+
+        assert!(bytes.len() < bundle.to_json().unwrap().len());
+        assert_eq!(ProofBundle::from_bytes(&bytes).unwrap(), bundle);
+        assert_eq!(ProofBundle::decode(&bytes).unwrap(), bundle);
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn compresses_the_proof() {
+        use compression::ProofEncoding;
+
+        let bundle = bundle().compressed(ProofEncoding::Zstd).unwrap();
+        assert!(bundle.proof.starts_with("zstd:"));
+        assert_eq!(bundle.proof_json().unwrap(), "proof");
+
+        let recompressed = bundle.compressed(ProofEncoding::Gzip).unwrap();
+        assert!(recompressed.proof.starts_with("gzip:"));
+        assert_eq!(recompressed.proof_json().unwrap(), "proof");
+
+        let bytes = recompressed.to_bytes().unwrap();
+        assert_eq!(ProofBundle::decode(&bytes).unwrap(), recompressed);
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let mut bundle = bundle();
+        bundle.version = FORMAT_VERSION + 1;
+
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert!(matches!(
+            ProofBundle::from_json(&json),
+            Err(BundleError::UnsupportedVersion(_))
+        ));
+
+        let bytes = bincode::serialize(&bundle).unwrap();
+        assert!(matches!(
+            ProofBundle::from_bytes(&bytes),
+            Err(BundleError::UnsupportedVersion(_))
+        ));
+    }
+}
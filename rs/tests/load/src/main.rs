@@ -1,16 +1,18 @@
 use verity_client::client::{VerityClient, VerityClientConfig};
+use verity_client::Redaction;
+
+/// Number of requests proven by a single run
+const REQUESTS: usize = 20;
+
+/// Number of requests in flight at any time
+const CONCURRENCY: usize = 5;
 
 #[tokio::main()]
 async fn main() {
-    let mut count = 0;
-    while count < 20 {
-        let _ = f(&count).await;
-        count += 1;
-    }
-}
-
-async fn f(count: &i32) -> Result<(), reqwest::Error> {
-    println!("{} :: Proving a GET request using VerityClient...", count);
+    println!(
+        "Proving {} GET requests using VerityClient, {} at a time...",
+        REQUESTS, CONCURRENCY
+    );
 
     // let mut rng = rand::thread_rng();
     // let _signing_key = SigningKey::random(&mut rng);
@@ -19,18 +21,26 @@ async fn f(count: &i32) -> Result<(), reqwest::Error> {
 
     let client = VerityClient::new(config);
 
-    let json: serde_json::Value = client
-        .get("https://jsonplaceholder.typicode.com/posts/98")
-        .redact(String::from("res:body:dolor"))
-        .send()
-        .await
-        .unwrap()
-        .subject
-        .json()
-        .await
-        .unwrap();
-
-    println!("{} :: Response: {:?}", count, json);
+    let requests = (0..REQUESTS)
+        .map(|_| {
+            client
+                .get("https://jsonplaceholder.typicode.com/posts/98")
+                .redact(Redaction::response_json("dolor"))
+        })
+        .collect();
 
-    Ok(())
+    for (count, result) in client
+        .send_all(requests, CONCURRENCY)
+        .await
+        .into_iter()
+        .enumerate()
+    {
+        match result {
+            Ok(response) => {
+                let json: serde_json::Value = response.subject.json().await.unwrap();
+                println!("{} :: Response: {:?}", count, json);
+            }
+            Err(err) => println!("{} :: Error: {}", count, err),
+        }
+    }
 }
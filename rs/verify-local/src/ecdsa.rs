@@ -1,6 +1,7 @@
 use easy_hasher::easy_hasher;
 
 /// Validates an ECDSA signature against a given public key
+#[allow(clippy::ptr_arg)]
 pub fn validate_ecdsa_signature(
     signature_hex: &String,
     message: &String,
@@ -1,12 +1,13 @@
 use rs_merkle::{algorithms::Sha256, Hasher, MerkleTree};
 
 /// Generate a merkle tree by providing the leaves as hashed strings
+#[allow(clippy::ptr_arg)]
 pub fn generate_merkle_tree(leaves: &Vec<[u8; 32]>) -> MerkleTree<Sha256> {
-    let tree: MerkleTree<Sha256> = MerkleTree::<Sha256>::from_leaves(&leaves);
-    return tree;
+    MerkleTree::<Sha256>::from_leaves(leaves)
 }
 
 /// Validate that the provided root hash is the same as the one derived from building a tree out of the provided leaves
+#[allow(clippy::ptr_arg)]
 pub fn validate_merkle_tree(leaves: &Vec<String>, root_hash: &String) -> bool {
     // gather the leaves from the content of the verified proof
     // which is either the req/res pair or the has of the session proof
@@ -28,5 +29,5 @@ pub fn validate_merkle_tree(leaves: &Vec<String>, root_hash: &String) -> bool {
     // println!("derived_root_hash: {}", derived_root_hash);
     // println!("root_hash: {}", root_hash);
 
-    return derived_root_hash == root_hash;
+    derived_root_hash == root_hash
 }
@@ -4,6 +4,7 @@ use crate::config::Config;
 use candid::{CandidType, Decode};
 use ic_agent::{export::Principal, Agent};
 use serde::Deserialize;
+use verity_proof_bundle::ProofBundle;
 use verity_verify_local::{self, ecdsa::validate_ecdsa_signature, merkle::validate_merkle_tree};
 pub const DEFAULT_IC_GATEWAY_LOCAL: &str = "http://127.0.0.1:4943";
 pub const DEFAULT_IC_GATEWAY_MAINNET: &str = "https://icp0.io";
@@ -76,7 +77,7 @@ impl Verifier {
 
         // Verify the signature and the Merkle tree root
         let is_signature_valid =
-            validate_ecdsa_signature(signature_hex, &root_hash, &canister_public_key)?;
+            validate_ecdsa_signature(signature_hex, root_hash, &canister_public_key)?;
         let is_merkle_valid = validate_merkle_tree(&leaves, root_hash);
 
         // Return the verification result
@@ -130,4 +131,32 @@ impl Verifier {
 
         Ok(verification_response)
     }
+
+    /// Verifies proof bundles on-chain, see `verify_proof`.
+    ///
+    /// All bundles must have been notarized with the same notary public key, which
+    /// the canister verifies them against.
+    pub async fn verify_bundles(
+        &self,
+        bundles: &[ProofBundle],
+    ) -> Result<VerificationResponse, Box<dyn Error>> {
+        let notary_pub_key = match bundles {
+            [first, rest @ ..]
+                if rest
+                    .iter()
+                    .all(|bundle| bundle.notary_pub_key == first.notary_pub_key) =>
+            {
+                first.notary_pub_key.clone()
+            }
+            [] => return Err("no proof bundles to verify".into()),
+            _ => return Err("proof bundles are notarized with different notary keys".into()),
+        };
+
+        // The canister verifies proof JSON, so compressed proofs are decompressed here
+        let string_proofs = bundles
+            .iter()
+            .map(|bundle| Ok(bundle.proof_json()?.into_owned()))
+            .collect::<Result<_, Box<dyn Error>>>()?;
+        self.verify_proof(string_proofs, notary_pub_key).await
+    }
 }
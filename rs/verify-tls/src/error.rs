@@ -0,0 +1,88 @@
+use std::string::FromUtf8Error;
+
+use thiserror::Error;
+use verity_proof_bundle::BundleError;
+
+#[cfg(feature = "session")]
+use tlsn_core::proof::SessionProofError;
+
+/// Why a proof was rejected. The error of the underlying check is kept as the source.
+#[derive(Debug, Error)]
+pub enum VerifyTlsError {
+    /// The proof is not a JSON encoded TLS proof.
+    #[error("invalid proof: {0}")]
+    Deserialization(#[from] serde_json::Error),
+
+    /// The proof is compressed but could not be decompressed.
+    #[error("invalid compressed proof: {0}")]
+    Compression(#[from] BundleError),
+
+    /// The notary public key is not a PEM encoded P-256 public key.
+    #[cfg(feature = "session")]
+    #[error("invalid notary public key: {0}")]
+    NotaryKey(#[from] elliptic_curve::pkcs8::spki::Error),
+
+    /// The session header is not signed, or its signature does not verify against the
+    /// notary public key.
+    #[cfg(feature = "session")]
+    #[error("invalid notary signature: {0}")]
+    Signature(#[source] SessionProofError),
+
+    /// The server name, the TLS handshake or the server certificate chain failed
+    /// verification.
+    #[cfg(feature = "session")]
+    #[error("server identity verification failed: {0}")]
+    Certificate(#[source] SessionProofError),
+
+    /// A root certificate to verify the server certificate chain with is not a valid
+    /// DER encoded trust anchor.
+    #[cfg(feature = "test-roots")]
+    #[error("invalid root certificate: {0}")]
+    RootCertificate(String),
+
+    /// The revealed transcript does not match the commitments in the session header.
+    /// The source is the `SubstringsProofError` of `tlsn-core` or `tlsn-core-no-session`,
+    /// depending on the verification mode.
+    #[cfg(any(feature = "session", feature = "no-session"))]
+    #[error("substrings proof verification failed: {0}")]
+    Substrings(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    /// The revealed transcript is not valid UTF-8.
+    #[error("transcript is not valid UTF-8: {0}")]
+    Transcript(#[from] FromUtf8Error),
+
+    /// The server the proof was made with is not accepted by a `ServerNamePolicy`.
+    #[error("server identity rejected: {0}")]
+    ServerName(String),
+
+    /// The revealed transcript is not a sequence of complete HTTP/1.x requests and
+    /// responses.
+    #[error("invalid HTTP transcript: {0}")]
+    Http(String),
+}
+
+#[cfg(feature = "session")]
+impl From<SessionProofError> for VerifyTlsError {
+    fn from(err: SessionProofError) -> Self {
+        match err {
+            SessionProofError::MissingNotarySignature | SessionProofError::InvalidSignature(_) => {
+                VerifyTlsError::Signature(err)
+            }
+            _ => VerifyTlsError::Certificate(err),
+        }
+    }
+}
+
+#[cfg(feature = "session")]
+impl From<tlsn_core::proof::SubstringsProofError> for VerifyTlsError {
+    fn from(err: tlsn_core::proof::SubstringsProofError) -> Self {
+        VerifyTlsError::Substrings(Box::new(err))
+    }
+}
+
+#[cfg(feature = "no-session")]
+impl From<tlsn_core_no_session::proof::SubstringsProofError> for VerifyTlsError {
+    fn from(err: tlsn_core_no_session::proof::SubstringsProofError) -> Self {
+        VerifyTlsError::Substrings(Box::new(err))
+    }
+}
@@ -0,0 +1,494 @@
+//! HTTP exchanges revealed by a proof.
+//!
+//! The transcripts of a TLS session are the raw bytes sent to and received from the
+//! server. [`parse_http_exchanges`] parses them into the HTTP/1.x requests and responses
+//! they carry, so several requests made on one connection are told apart, and decodes
+//! chunked bodies.
+//!
+//! Redacted bytes are kept in place and read as `X`, and every [`RedactedBytes`] of a
+//! header value or body keeps the mask of which of its bytes the prover did not reveal.
+
+use httparse::Status;
+
+use crate::error::VerifyTlsError;
+use crate::transcript::RedactedBytes;
+
+/// Number of headers a request, response or chunked trailer is first parsed with. The
+/// buffer grows for messages with more headers.
+const INITIAL_HEADERS: usize = 64;
+
+/// A header of a request or response. Names are revealed as sent, values may be
+/// redacted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpHeader {
+    pub name: String,
+    pub value: RedactedBytes,
+}
+
+/// A request sent to the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: String,
+    /// The URL of the request, `https://` followed by the `Host` header and the request
+    /// target. Requests without a `Host` header keep the request target as is.
+    pub url: String,
+    pub headers: Vec<HttpHeader>,
+    /// The body, with chunked transfer-encoding decoded.
+    pub body: RedactedBytes,
+}
+
+impl HttpRequest {
+    /// The value of the first header named `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&RedactedBytes> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// A response received from the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub reason: String,
+    pub headers: Vec<HttpHeader>,
+    /// The body, with chunked transfer-encoding decoded.
+    pub body: RedactedBytes,
+}
+
+impl HttpResponse {
+    /// The value of the first header named `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&RedactedBytes> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// A request and the final response to it, as revealed by a verified proof.
+/// Informational `1xx` responses are skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedHttpExchange {
+    pub request: HttpRequest,
+    pub response: HttpResponse,
+    /// The DNS name of the server the exchange was made with, see
+    /// [`VerifiedTranscripts::server_name`](crate::VerifiedTranscripts::server_name).
+    /// `None` when parsed with [`parse_http_exchanges`], which only sees the transcripts.
+    pub server_name: Option<String>,
+}
+
+/// How the end of a message body is found.
+enum Framing {
+    Empty,
+    Length(usize),
+    Chunked,
+    /// The body runs until the server closes the connection.
+    Close,
+}
+
+/// Parses the sent and received transcripts of a TLS session into the HTTP exchanges
+/// they carry, in the order the requests were sent.
+///
+/// # Errors
+///
+/// This function fails if a transcript is not a sequence of complete HTTP/1.x messages,
+/// for example because the prover redacted the framing of a message, or if the number
+/// of requests and responses does not match.
+pub fn parse_http_exchanges(
+    sent: &RedactedBytes,
+    recv: &RedactedBytes,
+) -> Result<Vec<VerifiedHttpExchange>, VerifyTlsError> {
+    let mut requests = Vec::new();
+    let mut pos = 0;
+    while pos < sent.data.len() {
+        let (request, end) = parse_request(sent, pos)?;
+        requests.push(request);
+        pos = end;
+    }
+
+    let mut exchanges = Vec::with_capacity(requests.len());
+    let mut pos = 0;
+    for request in requests {
+        let response = loop {
+            if pos >= recv.data.len() {
+                return Err(http_error(format!(
+                    "no response to {} {}",
+                    request.method, request.url
+                )));
+            }
+
+            let (response, end) = parse_response(recv, pos, &request.method)?;
+            pos = end;
+            if !(100..200).contains(&response.status) {
+                break response;
+            }
+        };
+
+        exchanges.push(VerifiedHttpExchange {
+            request,
+            response,
+            server_name: None,
+        });
+    }
+
+    if pos < recv.data.len() {
+        return Err(http_error("response without a request"));
+    }
+
+    Ok(exchanges)
+}
+
+fn parse_request(
+    bytes: &RedactedBytes,
+    start: usize,
+) -> Result<(HttpRequest, usize), VerifyTlsError> {
+    let head = parse_head(|headers| {
+        let mut request = httparse::Request::new(headers);
+        Ok(match request.parse(&bytes.data[start..])? {
+            Status::Complete(len) => Some((
+                len,
+                request.method.unwrap_or_default().to_string(),
+                request.path.unwrap_or_default().to_string(),
+                collect_headers(bytes, request.headers),
+            )),
+            Status::Partial => None,
+        })
+    })
+    .map_err(|e| http_error(format!("invalid request: {}", e)))?;
+    let (head_len, method, target, headers) =
+        head.ok_or_else(|| http_error("truncated request head"))?;
+
+    let url = match find_header(&headers, "host") {
+        Some(host) if target.starts_with('/') => format!("https://{}{}", host.text(), target),
+        _ => target,
+    };
+
+    let framing = match message_framing(&headers)? {
+        Framing::Close => Framing::Empty,
+        framing => framing,
+    };
+    let (body, end) = read_body(bytes, start + head_len, framing)?;
+
+    Ok((
+        HttpRequest {
+            method,
+            url,
+            headers,
+            body,
+        },
+        end,
+    ))
+}
+
+fn parse_response(
+    bytes: &RedactedBytes,
+    start: usize,
+    method: &str,
+) -> Result<(HttpResponse, usize), VerifyTlsError> {
+    let head = parse_head(|headers| {
+        let mut response = httparse::Response::new(headers);
+        Ok(match response.parse(&bytes.data[start..])? {
+            Status::Complete(len) => Some((
+                len,
+                response.code.unwrap_or_default(),
+                response.reason.unwrap_or_default().to_string(),
+                collect_headers(bytes, response.headers),
+            )),
+            Status::Partial => None,
+        })
+    })
+    .map_err(|e| http_error(format!("invalid response: {}", e)))?;
+    let (head_len, status, reason, headers) =
+        head.ok_or_else(|| http_error("truncated response head"))?;
+
+    // Responses to HEAD and these statuses never have a body, whatever their headers say
+    let framing = if method.eq_ignore_ascii_case("HEAD")
+        || (100..200).contains(&status)
+        || status == 204
+        || status == 304
+    {
+        Framing::Empty
+    } else {
+        message_framing(&headers)?
+    };
+    let (body, end) = read_body(bytes, start + head_len, framing)?;
+
+    Ok((
+        HttpResponse {
+            status,
+            reason,
+            headers,
+            body,
+        },
+        end,
+    ))
+}
+
+/// Runs `parse` with a header buffer, growing the buffer and parsing again for as long
+/// as the message has too many headers for it. Every header takes at least one byte, so
+/// the buffer never grows past the size of the message.
+fn parse_head<'b, T>(
+    mut parse: impl FnMut(&mut [httparse::Header<'b>]) -> Result<T, httparse::Error>,
+) -> Result<T, httparse::Error> {
+    let mut len = INITIAL_HEADERS;
+    loop {
+        let mut headers = vec![httparse::EMPTY_HEADER; len];
+        match parse(&mut headers) {
+            Err(httparse::Error::TooManyHeaders) => len *= 2,
+            result => return result,
+        }
+    }
+}
+
+/// Copies parsed headers out of `bytes`, keeping the redaction mask of their values.
+fn collect_headers(bytes: &RedactedBytes, headers: &[httparse::Header<'_>]) -> Vec<HttpHeader> {
+    headers
+        .iter()
+        .map(|header| {
+            // The value borrows from `bytes.data`, so its offset locates its mask
+            let offset = header.value.as_ptr() as usize - bytes.data.as_ptr() as usize;
+            HttpHeader {
+                name: header.name.to_string(),
+                value: bytes.slice(offset..offset + header.value.len()),
+            }
+        })
+        .collect()
+}
+
+fn message_framing(headers: &[HttpHeader]) -> Result<Framing, VerifyTlsError> {
+    if let Some(encoding) = find_header(headers, "transfer-encoding") {
+        let chunked = encoding
+            .text()
+            .rsplit(',')
+            .next()
+            .is_some_and(|coding| coding.trim().eq_ignore_ascii_case("chunked"));
+        return Ok(if chunked {
+            Framing::Chunked
+        } else {
+            Framing::Close
+        });
+    }
+
+    match find_header(headers, "content-length") {
+        Some(length) if length.is_redacted() => Err(http_error("content length is redacted")),
+        Some(length) => length
+            .text()
+            .trim()
+            .parse()
+            .map(Framing::Length)
+            .map_err(|_| http_error(format!("invalid content length {}", length.text()))),
+        None => Ok(Framing::Close),
+    }
+}
+
+fn read_body(
+    bytes: &RedactedBytes,
+    start: usize,
+    framing: Framing,
+) -> Result<(RedactedBytes, usize), VerifyTlsError> {
+    match framing {
+        Framing::Empty => Ok((RedactedBytes::default(), start)),
+        Framing::Length(len) => {
+            let end = start
+                .checked_add(len)
+                .filter(|end| *end <= bytes.data.len())
+                .ok_or_else(|| http_error("truncated body"))?;
+            Ok((bytes.slice(start..end), end))
+        }
+        Framing::Chunked => decode_chunked(bytes, start),
+        Framing::Close => Ok((bytes.slice(start..bytes.data.len()), bytes.data.len())),
+    }
+}
+
+fn decode_chunked(
+    bytes: &RedactedBytes,
+    start: usize,
+) -> Result<(RedactedBytes, usize), VerifyTlsError> {
+    let mut body = RedactedBytes::default();
+    let mut pos = start;
+
+    loop {
+        let (size_len, size) = match httparse::parse_chunk_size(&bytes.data[pos..]) {
+            Ok(Status::Complete(chunk)) => chunk,
+            Ok(Status::Partial) => return Err(http_error("truncated chunked body")),
+            Err(_) => return Err(http_error("invalid chunk size")),
+        };
+        pos += size_len;
+
+        if size == 0 {
+            break;
+        }
+
+        let end = usize::try_from(size)
+            .ok()
+            .and_then(|size| pos.checked_add(size))
+            .ok_or_else(|| http_error("truncated chunked body"))?;
+        // The CRLF closing the chunk
+        let next = end
+            .checked_add(2)
+            .filter(|next| *next <= bytes.data.len())
+            .ok_or_else(|| http_error("truncated chunked body"))?;
+        if &bytes.data[end..next] != b"\r\n" {
+            return Err(http_error("chunk is not terminated by CRLF"));
+        }
+
+        body.extend(bytes.slice(pos..end));
+        pos = next;
+    }
+
+    // Trailers end with an empty line, which is all there is without trailers
+    let trailers = parse_head(|trailers| {
+        Ok(
+            match httparse::parse_headers(&bytes.data[pos..], trailers)? {
+                Status::Complete((len, _)) => Some(len),
+                Status::Partial => None,
+            },
+        )
+    });
+    match trailers {
+        Ok(Some(len)) => Ok((body, pos + len)),
+        Ok(None) => Err(http_error("truncated chunked body")),
+        Err(e) => Err(http_error(format!("invalid chunked trailer: {}", e))),
+    }
+}
+
+fn find_header<'a>(headers: &'a [HttpHeader], name: &str) -> Option<&'a RedactedBytes> {
+    headers
+        .iter()
+        .find(|header| header.name.eq_ignore_ascii_case(name))
+        .map(|header| &header.value)
+}
+
+fn http_error(reason: impl Into<String>) -> VerifyTlsError {
+    VerifyTlsError::Http(reason.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds transcript bytes, marking the first occurrence of each of `redacted` as
+    /// redacted, the way a verified transcript reads after `set_redacted(b'X')`.
+    fn transcript(text: &str, redacted: &[&str]) -> RedactedBytes {
+        let data = text.as_bytes().to_vec();
+        let mut mask = vec![false; data.len()];
+        for value in redacted {
+            let start = text.find(value).unwrap();
+            mask[start..start + value.len()].fill(true);
+        }
+        RedactedBytes {
+            data,
+            redacted: mask,
+        }
+    }
+
+    #[test]
+    fn parses_an_exchange_with_redactions() {
+        let sent = transcript(
+            "POST /echo HTTP/1.1\r\nhost: api.example.com\r\nx-api-key: XXXXXXXXXX\r\ncontent-length: 5\r\n\r\nhello",
+            &["XXXXXXXXXX"],
+        );
+        let recv = transcript(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 17\r\n\r\n{\"key\":XXXXXXXXX}",
+            &["XXXXXXXXX"],
+        );
+
+        let exchanges = parse_http_exchanges(&sent, &recv).unwrap();
+        assert_eq!(exchanges.len(), 1);
+
+        let VerifiedHttpExchange {
+            request,
+            response,
+            server_name,
+        } = &exchanges[0];
+        assert_eq!(*server_name, None);
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.url, "https://api.example.com/echo");
+        assert!(request.header("X-API-KEY").unwrap().is_redacted());
+        assert!(!request.header("host").unwrap().is_redacted());
+        assert_eq!(request.body.data, b"hello");
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.reason, "OK");
+        assert_eq!(response.body.text(), r#"{"key":XXXXXXXXX}"#);
+        assert_eq!(
+            response.body.redacted.iter().position(|redacted| *redacted),
+            Some(7)
+        );
+        assert!(!response.body.redacted[16]);
+    }
+
+    #[test]
+    fn decodes_chunked_bodies_and_pipelined_requests() {
+        let sent = transcript(
+            "GET /a HTTP/1.1\r\nhost: example.com\r\n\r\nHEAD /b HTTP/1.1\r\nhost: example.com\r\n\r\nGET /c HTTP/1.1\r\nhost: example.com\r\n\r\n",
+            &[],
+        );
+        let recv = transcript(
+            "HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n4\r\n{\"a\"\r\n6;ext=1\r\n:XXXX}\r\n0\r\nx-trailer: 1\r\n\r\n\
+             HTTP/1.1 200 OK\r\ncontent-length: 10\r\n\r\n\
+             HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 204 No Content\r\n\r\n",
+            &["XXXX"],
+        );
+
+        let exchanges = parse_http_exchanges(&sent, &recv).unwrap();
+        let urls: Vec<&str> = exchanges.iter().map(|e| e.request.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/a",
+                "https://example.com/b",
+                "https://example.com/c"
+            ]
+        );
+
+        let body = &exchanges[0].response.body;
+        assert_eq!(body.text(), r#"{"a":XXXX}"#);
+        assert_eq!(
+            body.redacted,
+            [false, false, false, false, false, true, true, true, true, false]
+        );
+        assert!(exchanges[1].response.body.data.is_empty());
+        assert_eq!(exchanges[2].response.status, 204);
+    }
+
+    #[test]
+    fn parses_messages_with_many_headers() {
+        let many: String = (0..200).map(|i| format!("x-h{}: {}\r\n", i, i)).collect();
+        let sent = transcript(
+            &format!("GET / HTTP/1.1\r\nhost: example.com\r\n{}\r\n", many),
+            &[],
+        );
+        let recv = transcript(
+            &format!(
+                "HTTP/1.1 200 OK\r\n{}transfer-encoding: chunked\r\n\r\n2\r\nok\r\n0\r\n{}\r\n",
+                many, many
+            ),
+            &[],
+        );
+
+        let exchanges = parse_http_exchanges(&sent, &recv).unwrap();
+        assert_eq!(exchanges[0].request.headers.len(), 201);
+        assert_eq!(exchanges[0].response.headers.len(), 201);
+        assert_eq!(
+            exchanges[0].response.header("x-h199").unwrap().text(),
+            "199"
+        );
+        assert_eq!(exchanges[0].response.body.text(), "ok");
+    }
+
+    #[test]
+    fn rejects_unparsable_transcripts() {
+        let sent = transcript("GET / HTTP/1.1\r\nhost: example.com\r\n\r\n", &[]);
+
+        for recv in [
+            "",
+            "HTTP/1.1 200 OK\r\ncontent-length: 10\r\n\r\nshort",
+            "HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\nXX\r\nbody",
+            "HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\nffffffffffffffff\r\n{}\r\n0\r\n\r\n",
+            "HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\nHTTP/1.1 200 OK\r\n\r\n",
+        ] {
+            let result = parse_http_exchanges(&sent, &transcript(recv, &[]));
+            assert!(matches!(result, Err(VerifyTlsError::Http(_))), "{:?}", recv);
+        }
+
+        let recv = transcript("HTTP/1.1 200 OK\r\ncontent-length: XX\r\n\r\n", &["XX"]);
+        assert!(parse_http_exchanges(&sent, &recv).is_err());
+    }
+}
@@ -1,14 +1,70 @@
-#[cfg(all(feature = "no-session", feature = "session"))]
-compile_error!("feature \"no-session\" and feature \"session\" cannot be enabled at the same time");
+//! Verification of Verity TLS proofs.
+//!
+//! The `session` feature verifies full proofs, including the notary signature over the
+//! session header and the server identity. The `no-session` feature verifies only the
+//! revealed substrings against the session header, for zkVM guests which cannot run the
+//! session checks. Both features may be enabled together, in which case the proof
+//! functions at the crate root are those of `session`, and a [`VerificationMode`] picks
+//! either at runtime.
+//!
+//! The `test-roots` feature adds `verify_proof_bytes_with_roots`, which trusts given root
+//! certificates instead of the `webpki-roots`. It is meant for tests against
+//! `verity-mock-prover` and should not be enabled in production verifiers.
 
 use cfg_if::cfg_if;
 
+mod error;
+mod http;
+#[cfg(any(feature = "session", feature = "no-session"))]
+mod mode;
+mod server;
+mod transcript;
+
+pub use error::VerifyTlsError;
+pub use http::{parse_http_exchanges, HttpHeader, HttpRequest, HttpResponse, VerifiedHttpExchange};
+#[cfg(any(feature = "session", feature = "no-session"))]
+pub use mode::VerificationMode;
+pub use server::ServerNamePolicy;
+pub use transcript::{RedactedBytes, VerifiedTranscripts};
+
+#[cfg(feature = "compression")]
+pub use verity_proof_bundle::compression::ProofEncoding;
+pub use verity_proof_bundle::ProofBundle;
+
+#[cfg(feature = "no-session")]
+pub mod no_session;
+#[cfg(feature = "session")]
+pub mod session;
+
 cfg_if! {
   if #[cfg(feature = "session")] {
-    mod session;
-    pub use session::{verify_proof, verify_session};
+    pub use session::{
+        verify_bundle, verify_bundle_bytes, verify_http_proof, verify_proof, verify_proof_bytes,
+        verify_proof_for_server, verify_session, SUPPORTED_NOTARY_VERSIONS,
+    };
+    #[cfg(feature = "compression")]
+    pub use session::verify_compressed_proof;
+    #[cfg(feature = "test-roots")]
+    pub use session::verify_proof_bytes_with_roots;
   } else if #[cfg(feature = "no-session")] {
-    mod no_session;
-    pub use no_session::verify_proof;
+    pub use no_session::{
+        verify_bundle, verify_bundle_bytes, verify_http_proof, verify_proof, verify_proof_bytes,
+        SUPPORTED_NOTARY_VERSIONS,
+    };
+    #[cfg(feature = "compression")]
+    pub use no_session::verify_compressed_proof;
   }
 }
+
+/// The JSON of the proof of a bundle. Compressed proofs are decompressed with the
+/// `compression` feature, and are rejected as invalid without it.
+#[cfg(any(feature = "session", feature = "no-session"))]
+fn bundle_proof(bundle: &ProofBundle) -> Result<String, VerifyTlsError> {
+    cfg_if! {
+      if #[cfg(feature = "compression")] {
+        Ok(bundle.proof_json()?.into_owned())
+      } else {
+        Ok(bundle.proof.clone())
+      }
+    }
+}
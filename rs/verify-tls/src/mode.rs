@@ -0,0 +1,68 @@
+//! Runtime selection of how a proof is verified.
+
+use crate::error::VerifyTlsError;
+use crate::http::VerifiedHttpExchange;
+use crate::server::ServerNamePolicy;
+use crate::transcript::VerifiedTranscripts;
+
+#[cfg(feature = "no-session")]
+use crate::no_session;
+#[cfg(feature = "session")]
+use crate::session;
+
+/// How a proof is verified, for builds that enable both the `session` and `no-session`
+/// features, such as a host that checks full proofs and also the substring-only
+/// verification its zkVM guest performs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationMode {
+    /// Verify the notary signature over the session header against `notary_pub_key`,
+    /// the server identity and the revealed substrings.
+    #[cfg(feature = "session")]
+    Session { notary_pub_key: String },
+    /// Verify the revealed substrings against the session header only.
+    #[cfg(feature = "no-session")]
+    NoSession,
+}
+
+impl VerificationMode {
+    /// Verifies a proof in this mode, returning the `(recv, sent)` transcripts like
+    /// `verify_proof`.
+    pub fn verify_proof(&self, proof: &str) -> Result<(String, String), VerifyTlsError> {
+        self.verify_proof_bytes(proof)?.into_strings()
+    }
+
+    /// Verifies a proof in this mode, returning the transcripts as bytes like
+    /// `verify_proof_bytes`.
+    pub fn verify_proof_bytes(&self, proof: &str) -> Result<VerifiedTranscripts, VerifyTlsError> {
+        match self {
+            #[cfg(feature = "session")]
+            VerificationMode::Session { notary_pub_key } => {
+                session::verify_proof_bytes(proof, notary_pub_key)
+            }
+            #[cfg(feature = "no-session")]
+            VerificationMode::NoSession => no_session::verify_proof_bytes(proof),
+        }
+    }
+
+    /// Verifies a proof in this mode, and checks its server name against `policy`. In
+    /// the `NoSession` mode the server name is unknown, so any restricting policy
+    /// rejects the proof.
+    pub fn verify_proof_for_server(
+        &self,
+        proof: &str,
+        policy: &ServerNamePolicy,
+    ) -> Result<VerifiedTranscripts, VerifyTlsError> {
+        let transcripts = self.verify_proof_bytes(proof)?;
+        policy.check(&transcripts)?;
+        Ok(transcripts)
+    }
+
+    /// Verifies a proof in this mode and parses the HTTP exchanges it reveals, like
+    /// `verify_http_proof`.
+    pub fn verify_http_proof(
+        &self,
+        proof: &str,
+    ) -> Result<Vec<VerifiedHttpExchange>, VerifyTlsError> {
+        self.verify_proof_bytes(proof)?.http_exchanges()
+    }
+}
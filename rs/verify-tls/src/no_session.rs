@@ -1,11 +1,43 @@
+//! Verification of the revealed substrings of a proof against its session header,
+//! without checking the notary signature or the server identity.
+
 use tlsn_core_no_session::proof::{SessionProof, TlsProof};
+use tlsn_core_no_session::RedactedTranscript;
+use verity_proof_bundle::ProofBundle;
+
+#[cfg(feature = "compression")]
+use verity_proof_bundle::compression::decompress_proof;
+
+use crate::bundle_proof;
+use crate::error::VerifyTlsError;
+use crate::http::VerifiedHttpExchange;
+use crate::transcript::{RedactedBytes, VerifiedTranscripts};
+
+/// Notary versions whose proofs this module verifies, as a semver requirement.
+/// `tlsn-core-no-session` is derived from `tlsn-core` v0.1.0-alpha.6.
+pub const SUPPORTED_NOTARY_VERSIONS: &str = "=0.1.0-alpha.6";
 
 /// A simple verifier which reads a proof generated by `simple_prover.rs` from "proof.json", verifies
 /// it and prints the verified data to the console.
-pub fn verify_proof(proof: &String) -> Result<(String, String), String> {
+///
+/// Returns the `(recv, sent)` transcripts, with redacted bytes replaced by 'X'. Use
+/// `verify_proof_bytes` for transcripts which may not be text.
+pub fn verify_proof(proof: &str) -> Result<(String, String), VerifyTlsError> {
+    verify_proof_bytes(proof)?.into_strings()
+}
+
+/// Verifies a proof like `verify_proof`, and parses the revealed transcripts into the
+/// HTTP exchanges they carry, keeping a mask of the bytes the prover redacted. The
+/// server name of the exchanges is `None`, since the session is not verified.
+pub fn verify_http_proof(proof: &str) -> Result<Vec<VerifiedHttpExchange>, VerifyTlsError> {
+    verify_proof_bytes(proof)?.http_exchanges()
+}
+
+/// Verifies a proof like `verify_proof`, and returns the revealed transcripts as bytes
+/// along with the ranges the prover redacted.
+pub fn verify_proof_bytes(proof: &str) -> Result<VerifiedTranscripts, VerifyTlsError> {
     // Deserialize the proof
-    let proof: TlsProof =
-        serde_json::from_str(proof.as_str()).or(Err("INVALID PROOF".to_owned()))?;
+    let proof: TlsProof = serde_json::from_str(proof)?;
 
     let TlsProof {
         // The session proof establishes the identity of the server and the commitments
@@ -27,16 +59,45 @@ pub fn verify_proof(proof: &String) -> Result<(String, String), String> {
     // Verify the substrings proof against the session header.
     //
     // This returns the redacted transcripts
-    let (mut sent, mut recv) = substrings
-        .verify(&header)
-        .or(Err("PROOF VERIFICATION FAILED".to_string()))?;
-
-    // Replace the bytes which the Prover chose not to disclose with 'X'
-    sent.set_redacted(b'X');
-    recv.set_redacted(b'X');
-
-    Ok((
-        String::from_utf8(recv.data().to_vec()).unwrap(),
-        String::from_utf8(sent.data().to_vec()).unwrap(),
-    ))
+    let (sent, recv) = substrings.verify(&header)?;
+
+    Ok(VerifiedTranscripts {
+        sent: redacted_bytes(sent),
+        recv: redacted_bytes(recv),
+        // Proofs without a session carry no server identity
+        server_name: None,
+    })
+}
+
+/// Verifies a proof given as raw bytes, which may be gzip or zstd compressed.
+#[cfg(feature = "compression")]
+pub fn verify_compressed_proof(proof: &[u8]) -> Result<(String, String), VerifyTlsError> {
+    verify_proof(&decompress_proof(proof)?)
+}
+
+/// Verifies the proof of a bundle. Session proofs are not checked against the notary
+/// public key in this mode, so `bundle.notary_pub_key` is not used.
+pub fn verify_bundle(bundle: &ProofBundle) -> Result<(String, String), VerifyTlsError> {
+    verify_bundle_bytes(bundle)?.into_strings()
+}
+
+/// Verifies the proof of a bundle like `verify_bundle`, and returns the revealed
+/// transcripts as bytes.
+pub fn verify_bundle_bytes(bundle: &ProofBundle) -> Result<VerifiedTranscripts, VerifyTlsError> {
+    verify_proof_bytes(&bundle_proof(bundle)?)
+}
+
+/// The data of a transcript, with redacted bytes set to `X`, and its redaction mask.
+fn redacted_bytes(mut transcript: RedactedTranscript) -> RedactedBytes {
+    transcript.set_redacted(b'X');
+
+    let mut redacted = vec![false; transcript.data().len()];
+    for range in transcript.redacted().iter_ranges() {
+        redacted[range].fill(true);
+    }
+
+    RedactedBytes {
+        data: transcript.data().to_vec(),
+        redacted,
+    }
 }
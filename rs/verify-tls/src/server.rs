@@ -0,0 +1,168 @@
+//! Checks on the identity of the server a proof was made with.
+//!
+//! With a session, the proof names the server it was made with, and the name is
+//! authenticated against the certificate chain of the TLS handshake. That alone does not
+//! tie the proof to any particular server, so callers which expect data from a known
+//! domain restrict the accepted names with a [`ServerNamePolicy`].
+
+use crate::error::VerifyTlsError;
+use crate::transcript::VerifiedTranscripts;
+
+/// Which server names a proof is accepted from. The default policy accepts any server.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerNamePolicy {
+    allowed_hosts: Vec<String>,
+    match_request_host: bool,
+}
+
+impl ServerNamePolicy {
+    /// A policy accepting any server.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accepts proofs made with `host`. Once a host is added, proofs made with any other
+    /// server are rejected, so the hosts added form an allowlist. Names are compared
+    /// ignoring case.
+    pub fn with_allowed_host(mut self, host: impl Into<String>) -> Self {
+        self.allowed_hosts.push(host.into());
+        self
+    }
+
+    /// Requires the `Host` header of every request in the transcript to name the server
+    /// the proof was made with, so a request cannot claim to be sent to another domain.
+    /// Requests which redact their `Host` header are rejected.
+    pub fn with_request_host_match(mut self) -> Self {
+        self.match_request_host = true;
+        self
+    }
+
+    /// Checks the server of verified transcripts against this policy.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`VerifyTlsError::ServerName`] if the server is not
+    /// accepted, or if the policy restricts the server and the transcripts carry no
+    /// server name, as with proofs verified without a session.
+    pub fn check(&self, transcripts: &VerifiedTranscripts) -> Result<(), VerifyTlsError> {
+        if self.allowed_hosts.is_empty() && !self.match_request_host {
+            return Ok(());
+        }
+
+        let server_name = transcripts
+            .server_name
+            .as_deref()
+            .ok_or_else(|| server_error("the proof does not authenticate a server name"))?;
+
+        if !self.allowed_hosts.is_empty()
+            && !self
+                .allowed_hosts
+                .iter()
+                .any(|host| host.eq_ignore_ascii_case(server_name))
+        {
+            return Err(server_error(format!(
+                "{} is not an allowed host",
+                server_name
+            )));
+        }
+
+        if self.match_request_host {
+            for exchange in transcripts.http_exchanges()? {
+                let request = &exchange.request;
+                let host = request
+                    .header("host")
+                    .filter(|host| !host.is_redacted())
+                    .ok_or_else(|| {
+                        server_error(format!(
+                            "request to {} does not reveal its host",
+                            request.url
+                        ))
+                    })?;
+
+                let host = host.text();
+                if !host_name(host.trim()).eq_ignore_ascii_case(server_name) {
+                    return Err(server_error(format!(
+                        "request host {} differs from server {}",
+                        host, server_name
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The name of a `Host` header value, without its port.
+fn host_name(host: &str) -> &str {
+    match host.rsplit_once(':') {
+        Some((name, port)) if !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => name,
+        _ => host,
+    }
+}
+
+fn server_error(reason: impl Into<String>) -> VerifyTlsError {
+    VerifyTlsError::ServerName(reason.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::RedactedBytes;
+
+    fn transcripts(server_name: Option<&str>, host: &str) -> VerifiedTranscripts {
+        let sent = format!("GET / HTTP/1.1\r\nhost: {}\r\n\r\n", host).into_bytes();
+        let recv = b"HTTP/1.1 204 No Content\r\n\r\n".to_vec();
+
+        VerifiedTranscripts {
+            sent: RedactedBytes {
+                redacted: vec![false; sent.len()],
+                data: sent,
+            },
+            recv: RedactedBytes {
+                redacted: vec![false; recv.len()],
+                data: recv,
+            },
+            server_name: server_name.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn checks_the_host_allowlist() {
+        let policy = ServerNamePolicy::new()
+            .with_allowed_host("api.example.com")
+            .with_allowed_host("example.org");
+
+        assert!(policy
+            .check(&transcripts(Some("API.example.com"), "api.example.com"))
+            .is_ok());
+        assert!(policy
+            .check(&transcripts(Some("example.org"), "example.org"))
+            .is_ok());
+        assert!(matches!(
+            policy.check(&transcripts(Some("evil.com"), "api.example.com")),
+            Err(VerifyTlsError::ServerName(_))
+        ));
+        assert!(policy.check(&transcripts(None, "api.example.com")).is_err());
+        assert!(ServerNamePolicy::new()
+            .check(&transcripts(None, "api.example.com"))
+            .is_ok());
+    }
+
+    #[test]
+    fn matches_request_hosts() {
+        let policy = ServerNamePolicy::new().with_request_host_match();
+
+        assert!(policy
+            .check(&transcripts(Some("example.com"), "example.com:443"))
+            .is_ok());
+        assert!(policy
+            .check(&transcripts(Some("example.com"), "other.com"))
+            .is_err());
+
+        let mut redacted = transcripts(Some("example.com"), "example.com");
+        let start = redacted.sent.text().find("example.com").unwrap();
+        redacted.sent.redacted[start..start + 11].fill(true);
+        assert!(policy.check(&redacted).is_err());
+    }
+}
@@ -1,27 +1,108 @@
-use tlsn_core::proof::{SessionProof, TlsProof};
+//! Verification of full proofs, checking the notary signature over the session header
+//! and the server identity before the revealed substrings.
+
+#[cfg(feature = "test-roots")]
+use tls_core::anchors::RootCertStore;
+#[cfg(feature = "test-roots")]
+use tls_core::key::Certificate;
+use tls_core::verify::WebPkiVerifier;
+use tlsn_core::proof::{default_cert_verifier, SessionProof, TlsProof};
+use tlsn_core::RedactedTranscript;
 
 use elliptic_curve::pkcs8::DecodePublicKey;
+use verity_proof_bundle::ProofBundle;
+
+#[cfg(feature = "compression")]
+use verity_proof_bundle::compression::decompress_proof;
+
+use crate::bundle_proof;
+use crate::error::VerifyTlsError;
+use crate::http::VerifiedHttpExchange;
+use crate::server::ServerNamePolicy;
+use crate::transcript::{RedactedBytes, VerifiedTranscripts};
+
+/// Notary versions whose session proofs this module verifies, as a semver requirement.
+/// Proofs are parsed with `tlsn-core` v0.1.0-alpha.6.
+pub const SUPPORTED_NOTARY_VERSIONS: &str = "=0.1.0-alpha.6";
 
 /// This verifies the identity of the server using a default certificate verifier which trusts
 /// the root certificates from the `webpki-roots` crate.
-pub fn verify_session(proof: &String, pub_key: &String) -> Result<(), String> {
-    let session: SessionProof =
-        serde_json::from_str(proof.as_str()).or(Err("INVALID PROOF".to_owned()))?;
+pub fn verify_session(proof: &str, pub_key: &str) -> Result<(), VerifyTlsError> {
+    let session: SessionProof = serde_json::from_str(proof)?;
 
-    let pub_key = p256::PublicKey::from_public_key_pem(pub_key.as_str())
-        .or(Err("INVALID PUBLIC KEY".to_owned()))?;
+    let pub_key = p256::PublicKey::from_public_key_pem(pub_key)?;
 
-    session
-        .verify_with_default_cert_verifier(pub_key)
-        .or(Err("INVALID PROOF SESSION".to_owned()))
+    Ok(session.verify_with_default_cert_verifier(pub_key)?)
 }
 
 /// A simple verifier which reads a proof generated by `simple_prover.rs` from "proof.json", verifies
 /// it and prints the verified data to the console.
-pub fn verify_proof(proof: &String, pub_key: &String) -> Result<(String, String), String> {
+///
+/// Returns the `(recv, sent)` transcripts, with redacted bytes replaced by 'X'. Use
+/// `verify_proof_bytes` for transcripts which may not be text, or for the name of the
+/// server the proof was made with.
+pub fn verify_proof(proof: &str, pub_key: &str) -> Result<(String, String), VerifyTlsError> {
+    verify_proof_bytes(proof, pub_key)?.into_strings()
+}
+
+/// Verifies a proof like `verify_proof_bytes`, and checks the authenticated server name
+/// against `policy`.
+pub fn verify_proof_for_server(
+    proof: &str,
+    pub_key: &str,
+    policy: &ServerNamePolicy,
+) -> Result<VerifiedTranscripts, VerifyTlsError> {
+    let transcripts = verify_proof_bytes(proof, pub_key)?;
+    policy.check(&transcripts)?;
+    Ok(transcripts)
+}
+
+/// Verifies a proof like `verify_proof`, and parses the revealed transcripts into the
+/// HTTP exchanges they carry, keeping a mask of the bytes the prover redacted. Each
+/// exchange names the server the proof was made with.
+pub fn verify_http_proof(
+    proof: &str,
+    pub_key: &str,
+) -> Result<Vec<VerifiedHttpExchange>, VerifyTlsError> {
+    verify_proof_bytes(proof, pub_key)?.http_exchanges()
+}
+
+/// Verifies a proof like `verify_proof`, and returns the revealed transcripts as bytes
+/// along with the ranges the prover redacted.
+pub fn verify_proof_bytes(
+    proof: &str,
+    pub_key: &str,
+) -> Result<VerifiedTranscripts, VerifyTlsError> {
+    verify_tls_proof(proof, pub_key, &default_cert_verifier())
+}
+
+/// Verifies a proof like `verify_proof_bytes`, trusting the DER encoded
+/// `root_certificates` instead of the `webpki-roots` ones to authenticate the server,
+/// for example the test authority of `verity-mock-prover`. Only built with the
+/// `test-roots` feature, so that production verifiers always trust the `webpki-roots`.
+#[cfg(feature = "test-roots")]
+pub fn verify_proof_bytes_with_roots(
+    proof: &str,
+    pub_key: &str,
+    root_certificates: &[Vec<u8>],
+) -> Result<VerifiedTranscripts, VerifyTlsError> {
+    let mut roots = RootCertStore::empty();
+    for root in root_certificates {
+        roots
+            .add(&Certificate(root.clone()))
+            .map_err(|e| VerifyTlsError::RootCertificate(format!("{:?}", e)))?;
+    }
+
+    verify_tls_proof(proof, pub_key, &WebPkiVerifier::new(roots, None))
+}
+
+fn verify_tls_proof(
+    proof: &str,
+    pub_key: &str,
+    cert_verifier: &WebPkiVerifier,
+) -> Result<VerifiedTranscripts, VerifyTlsError> {
     // Deserialize the proof
-    let proof: TlsProof =
-        serde_json::from_str(proof.as_str()).or(Err("INVALID PROOF".to_owned()))?;
+    let proof: TlsProof = serde_json::from_str(proof)?;
 
     let TlsProof {
         // The session proof establishes the identity of the server and the commitments
@@ -32,31 +113,68 @@ pub fn verify_proof(proof: &String, pub_key: &String) -> Result<(String, String)
         substrings,
     } = proof;
 
-    // Verify the session proof against the Notary's public key
-    let session_stringified = &serde_json::to_string(&session).unwrap();
-    verify_session(session_stringified, pub_key)?;
+    // Verify the session proof against the Notary's public key and the server
+    // certificate chain against the trusted roots
+    let notary_key = p256::PublicKey::from_public_key_pem(pub_key)?;
+    session.verify(notary_key, cert_verifier)?;
 
     let SessionProof {
         // The session header that was signed by the Notary is a succinct commitment to the TLS transcript.
         header,
-        // This is the server name, checked against the certificate chain shared in the TLS handshake.
-        // server_name,
+        // This holds the server name, checked against the certificate chain shared in the TLS handshake.
+        session_info,
         ..
     } = session;
 
     // Verify the substrings proof against the session header.
     //
     // This returns the redacted transcripts
-    let (mut sent, mut recv) = substrings
-        .verify(&header)
-        .or(Err("PROOF VERIFICATION FAILED".to_string()))?;
-
-    // Replace the bytes which the Prover chose not to disclose with 'X'
-    sent.set_redacted(b'X');
-    recv.set_redacted(b'X');
-
-    Ok((
-        String::from_utf8(recv.data().to_vec()).unwrap(),
-        String::from_utf8(sent.data().to_vec()).unwrap(),
-    ))
+    let (sent, recv) = substrings.verify(&header)?;
+
+    Ok(VerifiedTranscripts {
+        sent: redacted_bytes(sent),
+        recv: redacted_bytes(recv),
+        server_name: Some(session_info.server_name.as_str().to_string()),
+    })
+}
+
+/// Verifies a proof given as raw bytes, which may be gzip or zstd compressed, against
+/// the notary public key.
+#[cfg(feature = "compression")]
+pub fn verify_compressed_proof(
+    proof: &[u8],
+    pub_key: &str,
+) -> Result<(String, String), VerifyTlsError> {
+    verify_proof(&decompress_proof(proof)?, pub_key)
+}
+
+/// Verifies the proof of a bundle against the notary public key stored in the bundle.
+///
+/// The bundle is self-describing, so callers must check that `bundle.notary_pub_key`
+/// belongs to a notary they trust, or use `verify_proof` with a pinned key instead.
+pub fn verify_bundle(bundle: &ProofBundle) -> Result<(String, String), VerifyTlsError> {
+    verify_bundle_bytes(bundle)?.into_strings()
+}
+
+/// Verifies the proof of a bundle like `verify_bundle`, and returns the revealed
+/// transcripts as bytes.
+pub fn verify_bundle_bytes(bundle: &ProofBundle) -> Result<VerifiedTranscripts, VerifyTlsError> {
+    // Keys received over the proof stream have their line breaks escaped
+    let pub_key = bundle.notary_pub_key.replace("\\n", "\n");
+    verify_proof_bytes(&bundle_proof(bundle)?, &pub_key)
+}
+
+/// The data of a transcript, with redacted bytes set to `X`, and its redaction mask.
+fn redacted_bytes(mut transcript: RedactedTranscript) -> RedactedBytes {
+    transcript.set_redacted(b'X');
+
+    let mut redacted = vec![false; transcript.data().len()];
+    for range in transcript.redacted().iter_ranges() {
+        redacted[range].fill(true);
+    }
+
+    RedactedBytes {
+        data: transcript.data().to_vec(),
+        redacted,
+    }
 }
@@ -0,0 +1,142 @@
+//! Transcripts revealed by a proof, as bytes.
+//!
+//! A TLS session may carry any bytes, such as gzip, protobuf or image responses, so
+//! transcripts are verified as bytes. Redacted bytes read as `X`, and a mask records
+//! which bytes the prover did not reveal.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::error::VerifyTlsError;
+use crate::http::{parse_http_exchanges, VerifiedHttpExchange};
+
+/// Bytes of a transcript, with a mask of the bytes the prover redacted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RedactedBytes {
+    /// The bytes, with redacted bytes set to `X`.
+    pub data: Vec<u8>,
+    /// Whether the byte at the same index of `data` was redacted.
+    pub redacted: Vec<bool>,
+}
+
+impl RedactedBytes {
+    /// Whether any of the bytes was redacted.
+    pub fn is_redacted(&self) -> bool {
+        self.redacted.contains(&true)
+    }
+
+    /// The ranges of redacted bytes, in ascending order.
+    pub fn redacted_ranges(&self) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for (index, redacted) in self.redacted.iter().enumerate() {
+            if !redacted {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some(range) if range.end == index => range.end += 1,
+                _ => ranges.push(index..index + 1),
+            }
+        }
+        ranges
+    }
+
+    /// The bytes as text, with invalid UTF-8 replaced.
+    pub fn text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.data)
+    }
+
+    pub(crate) fn slice(&self, range: Range<usize>) -> RedactedBytes {
+        RedactedBytes {
+            data: self.data[range.clone()].to_vec(),
+            redacted: self.redacted[range].to_vec(),
+        }
+    }
+
+    pub(crate) fn extend(&mut self, other: RedactedBytes) {
+        self.data.extend(other.data);
+        self.redacted.extend(other.redacted);
+    }
+}
+
+/// The sent and received transcripts of a verified proof.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifiedTranscripts {
+    /// The bytes sent to the server.
+    pub sent: RedactedBytes,
+    /// The bytes received from the server.
+    pub recv: RedactedBytes,
+    /// The DNS name of the server, as authenticated by its certificate chain. `None` for
+    /// proofs verified without a session, which do not carry the server identity.
+    pub server_name: Option<String>,
+}
+
+impl VerifiedTranscripts {
+    /// Parses the transcripts into the HTTP exchanges they carry, each naming the server
+    /// of the transcripts.
+    ///
+    /// # Errors
+    ///
+    /// This method fails as [`parse_http_exchanges`] does.
+    pub fn http_exchanges(&self) -> Result<Vec<VerifiedHttpExchange>, VerifyTlsError> {
+        let mut exchanges = parse_http_exchanges(&self.sent, &self.recv)?;
+        for exchange in &mut exchanges {
+            exchange.server_name.clone_from(&self.server_name);
+        }
+        Ok(exchanges)
+    }
+
+    /// The `(recv, sent)` transcripts as text, as returned by `verify_proof`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if a transcript is not valid UTF-8.
+    pub fn into_strings(self) -> Result<(String, String), VerifyTlsError> {
+        Ok((
+            String::from_utf8(self.recv.data)?,
+            String::from_utf8(self.sent.data)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_binary_transcripts() {
+        let transcripts = VerifiedTranscripts {
+            sent: RedactedBytes {
+                data: b"GET /".to_vec(),
+                redacted: vec![false; 5],
+            },
+            recv: RedactedBytes {
+                data: vec![0x1f, 0x8b, b'X', b'X', 0xff, b'X'],
+                redacted: vec![false, false, true, true, false, true],
+            },
+            server_name: None,
+        };
+
+        assert_eq!(transcripts.recv.redacted_ranges(), [2..4, 5..6]);
+        assert!(transcripts.sent.redacted_ranges().is_empty());
+        assert!(matches!(
+            transcripts.into_strings(),
+            Err(VerifyTlsError::Transcript(_))
+        ));
+    }
+
+    #[test]
+    fn names_the_server_of_every_exchange() {
+        let text = |text: &str| RedactedBytes {
+            data: text.as_bytes().to_vec(),
+            redacted: vec![false; text.len()],
+        };
+        let transcripts = VerifiedTranscripts {
+            sent: text("GET / HTTP/1.1\r\nhost: example.com\r\n\r\n"),
+            recv: text("HTTP/1.1 204 No Content\r\n\r\n"),
+            server_name: Some("example.com".to_string()),
+        };
+
+        let exchanges = transcripts.http_exchanges().unwrap();
+        assert_eq!(exchanges[0].server_name.as_deref(), Some("example.com"));
+    }
+}
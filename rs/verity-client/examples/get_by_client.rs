@@ -1,4 +1,5 @@
 use verity_client::client::{VerityClient, VerityClientConfig};
+use verity_client::Redaction;
 
 #[tokio::main()]
 async fn main() -> anyhow::Result<()> {
@@ -6,14 +7,12 @@ async fn main() -> anyhow::Result<()> {
 
     println!("Proving a GET request using VerityClient...");
 
-    let config = VerityClientConfig {
-        prover_url: String::from("http://127.0.0.1:8080"),
-        proof_timeout: Some(std::time::Duration::from_millis(30000)),
-    };
+    let config = VerityClientConfig::new("http://127.0.0.1:8080")
+        .with_proof_timeout(std::time::Duration::from_millis(30000));
 
     let response = VerityClient::new(config)
         .get("https://jsonplaceholder.typicode.com/posts/98")
-        .redact(String::from("res:body:dolor"))
+        .redact(Redaction::response_json("dolor"))
         .send()
         .await?;
 
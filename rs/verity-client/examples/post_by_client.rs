@@ -1,4 +1,5 @@
 use verity_client::client::{VerityClient, VerityClientConfig};
+use verity_client::Redaction;
 
 #[tokio::main()]
 async fn main() -> anyhow::Result<()> {
@@ -6,10 +7,8 @@ async fn main() -> anyhow::Result<()> {
 
     println!("Proving a POST request using VerityClient...");
 
-    let config = VerityClientConfig {
-        prover_url: String::from("http://127.0.0.1:8080"),
-        proof_timeout: Some(std::time::Duration::from_millis(30000)),
-    };
+    let config = VerityClientConfig::new("http://127.0.0.1:8080")
+        .with_proof_timeout(std::time::Duration::from_millis(30000));
 
     let response = VerityClient::new(config)
         .post(String::from("https://jsonplaceholder.typicode.com/posts"))
@@ -20,7 +19,8 @@ async fn main() -> anyhow::Result<()> {
             "fullName": "John Smith",
             "favoriteActor": "Johnny Depp"
         }))
-        .redact(String::from("req:body:firstName, res:body:firstName"))
+        .redact(Redaction::request_json("firstName"))
+        .redact(Redaction::response_json("firstName"))
         .send()
         .await?;
 
@@ -1,58 +1,43 @@
 use verity_client::client::{VerityClient, VerityClientConfig};
+use verity_client::Redaction;
 
 #[tokio::main()]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
 
-    println!("Proving a POST request using VerityClient...");
+    println!("Proving multiple POST requests concurrently using VerityClient...");
 
-    let config = VerityClientConfig {
-        prover_url: String::from("http://127.0.0.1:8080"),
-        proof_timeout: Some(std::time::Duration::from_millis(30000)),
-    };
+    let config = VerityClientConfig::new("http://127.0.0.1:8080")
+        .with_proof_timeout(std::time::Duration::from_millis(30000));
 
     let verity_client = VerityClient::new(config);
 
-    let response = verity_client
-        .post(String::from("https://jsonplaceholder.typicode.com/posts"))
-        .json(&serde_json::json!({
-            "userId": 1000,
-            "firstName": "John",
-            "lastName": "Smith",
-            "fullName": "John Smith",
-            "favoriteActor": "Johnny Depp"
-        }))
-        .redact(String::from("req:body:firstName, res:body:firstName"))
-        .send()
-        .await?;
-
-    if response.subject.status().is_success() {
-        let json: serde_json::Value = response.subject.json().await.unwrap();
-        println!("json: {:#?}", json);
-        println!("response.proof.len(): {:#?}", response.proof.len());
-    } else {
-        anyhow::bail!(response.subject.status());
-    }
-
-    let response = verity_client
-        .post(String::from("https://jsonplaceholder.typicode.com/posts"))
-        .json(&serde_json::json!({
-            "userId": 1000,
-            "firstName": "John",
-            "lastName": "Smith",
-            "fullName": "John Smith",
-            "favoriteActor": "Johnny Depp"
-        }))
-        .redact(String::from("req:body:firstName, res:body:firstName"))
-        .send()
-        .await?;
-
-    if response.subject.status().is_success() {
-        let json: serde_json::Value = response.subject.json().await.unwrap();
-        println!("json: {:#?}", json);
-        println!("response.proof.len(): {:#?}", response.proof.len());
-    } else {
-        anyhow::bail!(response.subject.status());
+    let requests = (0..2)
+        .map(|_| {
+            verity_client
+                .post(String::from("https://jsonplaceholder.typicode.com/posts"))
+                .json(&serde_json::json!({
+                    "userId": 1000,
+                    "firstName": "John",
+                    "lastName": "Smith",
+                    "fullName": "John Smith",
+                    "favoriteActor": "Johnny Depp"
+                }))
+                .redact(Redaction::request_json("firstName"))
+                .redact(Redaction::response_json("firstName"))
+        })
+        .collect();
+
+    for response in verity_client.send_all(requests, 2).await {
+        let response = response?;
+
+        if response.subject.status().is_success() {
+            let json: serde_json::Value = response.subject.json().await.unwrap();
+            println!("json: {:#?}", json);
+            println!("response.proof.len(): {:#?}", response.proof.len());
+        } else {
+            anyhow::bail!(response.subject.status());
+        }
     }
 
     Ok(())
@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use http::header::AUTHORIZATION;
+use http::{HeaderMap, HeaderName, HeaderValue};
+
+use crate::error::VerityError;
+
+/// Prefix of the headers the prover consumes instead of forwarding them to the target.
+const PROVER_HEADER_PREFIX: &str = "t-";
+
+/// Produces the current bearer token for the prover gateway.
+pub type TokenProvider = Arc<dyn Fn() -> String + Send + Sync>;
+
+/// Credentials presented to an authenticating gateway in front of the prover.
+///
+/// Credentials are attached to every prover endpoint (`/proxy`, `/proof/{id}` and
+/// `/notaryinfo`). Bearer tokens are sent as a standard `Authorization` header, and
+/// header credentials under the name they were given. On `/proxy`, whose other headers
+/// are forwarded to the target, they are sent with a `T-` prefix instead, e.g.
+/// `T-AUTHORIZATION` or `T-X-API-KEY`: the prover strips `T-` headers before
+/// forwarding, so credentials never reach the target nor the notarized transcript.
+#[derive(Clone)]
+pub enum ProverAuth {
+    /// A static header, e.g. an API key.
+    Header(HeaderName, HeaderValue),
+    /// An `Authorization: Bearer` token, obtained before every prover call so that
+    /// rotating tokens are picked up.
+    Bearer(TokenProvider),
+    /// A TLS client certificate presented when connecting to the prover.
+    ClientCertificate(reqwest::Identity),
+}
+
+impl ProverAuth {
+    /// Authenticate with a static header, such as `x-api-key`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the header name or value is invalid.
+    pub fn header(name: &str, value: &str) -> Result<Self, VerityError> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| VerityError::Prover(format!("invalid auth header name: {}", e)))?;
+        let mut value = HeaderValue::from_str(value)?;
+        value.set_sensitive(true);
+
+        Ok(ProverAuth::Header(name, value))
+    }
+
+    /// Authenticate with a fixed bearer token.
+    pub fn bearer(token: impl Into<String>) -> Self {
+        let token = token.into();
+        ProverAuth::Bearer(Arc::new(move || token.clone()))
+    }
+
+    /// Authenticate with a bearer token returned by `provider`, which is called before
+    /// every prover call.
+    pub fn bearer_provider(provider: impl Fn() -> String + Send + Sync + 'static) -> Self {
+        ProverAuth::Bearer(Arc::new(provider))
+    }
+
+    /// Authenticate with a TLS client certificate.
+    ///
+    /// The certificate is installed on the client's default HTTP client. When a custom
+    /// client is supplied with `VerityClientConfig::with_http_client`, configure the
+    /// certificate on that client instead. Proofs cannot be received over
+    /// `ProofTransport::WebSocket` with a client certificate.
+    pub fn client_certificate(identity: reqwest::Identity) -> Self {
+        ProverAuth::ClientCertificate(identity)
+    }
+
+    /// The header carrying the credentials, if they are sent as a header.
+    pub(crate) fn header_pair(&self) -> Result<Option<(HeaderName, HeaderValue)>, VerityError> {
+        let (name, value) = match self {
+            ProverAuth::Header(name, value) => (name.clone(), value.clone()),
+            ProverAuth::Bearer(provider) => {
+                let mut value = HeaderValue::from_str(&format!("Bearer {}", provider()))?;
+                value.set_sensitive(true);
+                (AUTHORIZATION, value)
+            }
+            ProverAuth::ClientCertificate(_) => return Ok(None),
+        };
+
+        Ok(Some((name, value)))
+    }
+
+    /// Adds the credentials to the headers of a request proxied by the prover, under
+    /// their `T-` prefixed name so that they are not forwarded to the target.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the headers already contain the prefixed credential header,
+    /// which would otherwise be mistaken for the gateway credentials.
+    pub(crate) fn apply(&self, headers: &mut HeaderMap) -> Result<(), VerityError> {
+        if let Some((name, value)) = self.header_pair()? {
+            let name = prover_header(&name)?;
+            if headers.contains_key(&name) {
+                return Err(VerityError::Prover(format!(
+                    "request header `{}` conflicts with the prover credentials",
+                    name
+                )));
+            }
+            headers.insert(name, value);
+        }
+
+        Ok(())
+    }
+}
+
+/// The name `name` is sent under to the prover, e.g. `t-x-api-key` for `x-api-key`.
+fn prover_header(name: &HeaderName) -> Result<HeaderName, VerityError> {
+    if name.as_str().starts_with(PROVER_HEADER_PREFIX) {
+        return Ok(name.clone());
+    }
+
+    HeaderName::from_bytes(format!("{}{}", PROVER_HEADER_PREFIX, name).as_bytes())
+        .map_err(|e| VerityError::Prover(format!("invalid auth header name: {}", e)))
+}
@@ -0,0 +1,291 @@
+//! A blocking Verity client.
+//!
+//! The types in this module mirror [`crate::client::VerityClient`],
+//! [`crate::request::RequestBuilder`] and [`crate::client::VerityResponse`], but block
+//! the current thread instead of returning futures, like `reqwest::blocking`. Each
+//! client drives the async client on its own single-threaded runtime, so proof
+//! timeouts and cancellation behave exactly as with `execute_request`.
+//!
+//! The blocking client must not be used from within an async runtime, as blocking on
+//! a runtime from inside another one panics.
+
+use std::convert::TryFrom;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+use reqwest::{Body, IntoUrl, Request, Url};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::runtime::Runtime;
+use verity_proof_bundle::ProofBundle;
+
+use crate::client::{self, NotaryInformation, VerityClientConfig};
+use crate::error::VerityError;
+use crate::proof::ProvenRequest;
+use crate::redaction::Redaction;
+use crate::request;
+
+/// A blocking client for proving requests through a Verity prover.
+#[derive(Clone)]
+pub struct VerityClient {
+    inner: client::VerityClient,
+    runtime: Arc<Runtime>,
+}
+
+/// A blocking builder for a proven request, see [`crate::request::RequestBuilder`].
+#[must_use = "RequestBuilder does nothing until you 'send' it"]
+pub struct RequestBuilder {
+    inner: request::RequestBuilder,
+    runtime: Arc<Runtime>,
+}
+
+/// A proven response, see [`crate::client::VerityResponse`].
+pub struct VerityResponse {
+    pub subject: Response,
+    pub proof: String,
+    pub notary_pub_key: String,
+    /// The request the proof was produced for.
+    pub request: ProvenRequest,
+}
+
+/// The response relayed by the prover. The body is read on demand.
+pub struct Response {
+    inner: reqwest::Response,
+    runtime: Arc<Runtime>,
+}
+
+impl VerityClient {
+    /// Creates a new blocking `VerityClient` with the given configuration.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the runtime backing the client cannot be created, or if
+    /// it is called from within an async runtime.
+    pub fn new(config: VerityClientConfig) -> Self {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to create the blocking client runtime");
+
+        // Entered so that a client certificate or custom client can be set up
+        let inner = {
+            let _guard = runtime.enter();
+            client::VerityClient::new(config)
+        };
+
+        Self {
+            inner,
+            runtime: Arc::new(runtime),
+        }
+    }
+
+    /// Convenience method to make a `GET` request to a URL.
+    pub fn get<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::GET, url)
+    }
+
+    /// Convenience method to make a `POST` request to a URL.
+    pub fn post<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::POST, url)
+    }
+
+    /// Starts building a `Request` with the specified `Method` and `Url`.
+    pub fn request<U: IntoUrl>(&self, method: Method, url: U) -> RequestBuilder {
+        RequestBuilder {
+            inner: self.inner.request(method, url),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Sends all `requests` concurrently, see [`crate::client::VerityClient::send_all()`].
+    pub fn send_all(
+        &self,
+        requests: Vec<RequestBuilder>,
+        concurrency: usize,
+    ) -> Vec<Result<VerityResponse, VerityError>> {
+        let requests = requests.into_iter().map(|request| request.inner).collect();
+        self.runtime
+            .block_on(self.inner.send_all(requests, concurrency))
+            .into_iter()
+            .map(|result| result.map(|response| VerityResponse::new(response, &self.runtime)))
+            .collect()
+    }
+
+    /// Executes a `Request` and blocks until its proof arrives.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the request cannot be sent or if proof cannot be obtained.
+    pub fn execute(&mut self, request: Request) -> Result<VerityResponse, VerityError> {
+        let response = self.runtime.block_on(self.inner.execute(request))?;
+        Ok(VerityResponse::new(response, &self.runtime))
+    }
+
+    /// Get the information of the connected notary
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the prover cannot be reached, the notary key is not in
+    /// the configured trust list, or the notary version is not supported.
+    pub fn get_notary_info(&self) -> Result<NotaryInformation, VerityError> {
+        self.runtime.block_on(self.inner.get_notary_info())
+    }
+}
+
+impl RequestBuilder {
+    /// Add a `Header` to this Request.
+    pub fn header<K, V>(self, key: K, value: V) -> Self
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<http::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
+    {
+        self.map(|inner| inner.header(key, value))
+    }
+
+    /// Add a set of Headers to the existing ones on this Request.
+    pub fn headers(self, headers: HeaderMap) -> Self {
+        self.map(|inner| inner.headers(headers))
+    }
+
+    /// Set the request body.
+    pub fn body<T: Into<Body>>(self, body: T) -> Self {
+        self.map(|inner| inner.body(body))
+    }
+
+    /// Send a JSON body.
+    pub fn json<T: Serialize + ?Sized>(self, json: &T) -> Self {
+        self.map(|inner| inner.json(json))
+    }
+
+    /// Add an instruction to prove failed request.
+    pub fn prove_failed_request(self) -> Self {
+        self.map(request::RequestBuilder::prove_failed_request)
+    }
+
+    /// Add a Redact instruction.
+    pub fn redact(self, redaction: Redaction) -> Self {
+        self.map(|inner| inner.redact(redaction))
+    }
+
+    /// Override the client's proof timeout for this request only.
+    pub fn proof_timeout(self, timeout: Duration) -> Self {
+        self.map(|inner| inner.proof_timeout(timeout))
+    }
+
+    /// Build a `Request`, which can be executed with `VerityClient::execute()`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the request or any of its redaction rules is invalid.
+    pub fn build(self) -> Result<Request, VerityError> {
+        self.inner.build()
+    }
+
+    /// Sends the request and blocks until the response and its proof arrive.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the request cannot be sent or if proof cannot be obtained.
+    pub fn send(self) -> Result<VerityResponse, VerityError> {
+        let response = self.runtime.block_on(self.inner.send())?;
+        Ok(VerityResponse::new(response, &self.runtime))
+    }
+
+    fn map(self, f: impl FnOnce(request::RequestBuilder) -> request::RequestBuilder) -> Self {
+        RequestBuilder {
+            inner: f(self.inner),
+            runtime: self.runtime,
+        }
+    }
+}
+
+impl VerityResponse {
+    fn new(response: client::VerityResponse, runtime: &Arc<Runtime>) -> Self {
+        Self {
+            subject: Response {
+                inner: response.subject,
+                runtime: runtime.clone(),
+            },
+            proof: response.proof,
+            notary_pub_key: response.notary_pub_key,
+            request: response.request,
+        }
+    }
+
+    /// Packs the proof into a `ProofBundle`, see
+    /// [`crate::client::VerityResponse::to_bundle()`].
+    pub fn to_bundle(&self) -> ProofBundle {
+        self.request.bundle(&self.proof, &self.notary_pub_key)
+    }
+
+    /// Verifies the proof, see [`crate::client::VerityResponse::verify()`].
+    ///
+    /// # Errors
+    ///
+    /// This method fails if no key of `trusted_keys` trusts the notary key of the proof,
+    /// if the response carries no proof, if the proof is invalid, or if the received
+    /// response differs from the notarized one.
+    #[cfg(feature = "verify")]
+    pub fn verify(
+        self,
+        trusted_keys: &[crate::notary::TrustedNotaryKey],
+    ) -> Result<crate::verify::VerifiedResponse, VerityError> {
+        let Response { inner, runtime } = self.subject;
+        let response = client::VerityResponse {
+            subject: inner,
+            proof: self.proof,
+            notary_pub_key: self.notary_pub_key,
+            request: self.request,
+        };
+
+        runtime.block_on(response.verify(trusted_keys))
+    }
+}
+
+impl Response {
+    /// Get the `StatusCode` of this `Response`.
+    pub fn status(&self) -> StatusCode {
+        self.inner.status()
+    }
+
+    /// Get the `Headers` of this `Response`.
+    pub fn headers(&self) -> &HeaderMap {
+        self.inner.headers()
+    }
+
+    /// Get the final `Url` of this `Response`.
+    pub fn url(&self) -> &Url {
+        self.inner.url()
+    }
+
+    /// Get the full response body as `Bytes`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the body cannot be read.
+    pub fn bytes(self) -> Result<Bytes, VerityError> {
+        Ok(self.runtime.block_on(self.inner.bytes())?)
+    }
+
+    /// Get the full response text.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the body cannot be read.
+    pub fn text(self) -> Result<String, VerityError> {
+        Ok(self.runtime.block_on(self.inner.text())?)
+    }
+
+    /// Deserialize the response body as JSON.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the body cannot be read or is not valid JSON for `T`.
+    pub fn json<T: DeserializeOwned>(self) -> Result<T, VerityError> {
+        Ok(self.runtime.block_on(self.inner.json())?)
+    }
+}
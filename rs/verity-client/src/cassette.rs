@@ -0,0 +1,281 @@
+//! Record and replay of proven requests.
+//!
+//! A [`Cassette`] in record mode saves every request made through the client, together
+//! with the response and the proof message, to a JSON file. In replay mode, the same
+//! file serves those responses and proofs without contacting a prover, which allows
+//! deterministic tests against real historical proofs.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+use reqwest::{Response, ResponseBuilderExt, Url};
+use serde::{Deserialize, Serialize};
+use tokio::select;
+use tokio_util::sync::CancellationToken;
+
+use crate::error::VerityError;
+use crate::notary::{check_notary_key, TrustedNotaryKey};
+use crate::proof::{Proof, ProofHandle};
+
+/// A recorded request, its response and its proof.
+///
+/// Bodies are stored hex encoded so binary payloads survive the round trip.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub method: String,
+    pub url: String,
+    pub request_body: String,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    pub notary_pub_key: String,
+    pub proof: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Record,
+    Replay,
+}
+
+struct Tape {
+    interactions: Vec<Interaction>,
+    replayed: Vec<bool>,
+}
+
+/// A cassette file that proven requests are recorded to or replayed from.
+#[derive(Clone)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: Mode,
+    tape: Arc<Mutex<Tape>>,
+}
+
+impl Cassette {
+    /// Records every proven request to `path`, replacing any existing cassette.
+    ///
+    /// The file is rewritten after each interaction, so it is complete even if the
+    /// process exits early.
+    pub fn record(path: impl AsRef<Path>) -> Self {
+        Self::new(path.as_ref(), Mode::Record, Vec::new())
+    }
+
+    /// Replays the interactions recorded in `path`. No prover is contacted.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the cassette cannot be read or parsed.
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self, VerityError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| VerityError::Cassette(format!("{}: {}", path.display(), e)))?;
+        let interactions = serde_json::from_str(&content)
+            .map_err(|e| VerityError::Cassette(format!("{}: {}", path.display(), e)))?;
+
+        Ok(Self::new(path, Mode::Replay, interactions))
+    }
+
+    fn new(path: &Path, mode: Mode, interactions: Vec<Interaction>) -> Self {
+        let replayed = vec![false; interactions.len()];
+        Self {
+            path: path.to_path_buf(),
+            mode,
+            tape: Arc::new(Mutex::new(Tape {
+                interactions,
+                replayed,
+            })),
+        }
+    }
+
+    /// Returns a copy of the interactions recorded or loaded so far.
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.tape.lock().unwrap().interactions.clone()
+    }
+
+    pub(crate) fn is_replay(&self) -> bool {
+        self.mode == Mode::Replay
+    }
+
+    /// Finds the recorded response and proof for a request.
+    ///
+    /// Interactions are replayed in recording order. Once every matching interaction
+    /// was replayed, the last one is served again. A recorded proof must be signed with
+    /// one of `trusted_keys`, as a live one must.
+    pub(crate) fn replay_request(
+        &self,
+        method: &Method,
+        url: &str,
+        body: &[u8],
+        trusted_keys: &[TrustedNotaryKey],
+    ) -> Result<(Response, ProofHandle), VerityError> {
+        let request_body = hex::encode(body);
+        let mut tape = self.tape.lock().unwrap();
+
+        let matching: Vec<usize> = tape
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| {
+                i.method == method.as_str() && i.url == url && i.request_body == request_body
+            })
+            .map(|(index, _)| index)
+            .collect();
+        let index = matching
+            .iter()
+            .copied()
+            .find(|&index| !tape.replayed[index])
+            .or_else(|| matching.last().copied())
+            .ok_or_else(|| {
+                VerityError::Cassette(format!("no recorded interaction for {} {}", method, url))
+            })?;
+        tape.replayed[index] = true;
+
+        let interaction = &tape.interactions[index];
+        if !interaction.proof.is_empty() {
+            check_notary_key(trusted_keys, &interaction.notary_pub_key)?;
+        }
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &interaction.headers {
+            headers.append(
+                HeaderName::from_bytes(name.as_bytes()).map_err(cassette_error)?,
+                HeaderValue::from_str(value)?,
+            );
+        }
+
+        let url = Url::parse(url).map_err(cassette_error)?;
+        let response = rebuild_response(
+            interaction.status,
+            headers,
+            &url,
+            decode(&interaction.body)?,
+        )?;
+
+        let proof = Proof {
+            notary_pub_key: interaction.notary_pub_key.clone(),
+            proof: interaction.proof.clone(),
+        };
+
+        Ok((response, ProofHandle::ready(proof)))
+    }
+
+    /// Records a live response and its proof once the proof arrives.
+    ///
+    /// The response body is read up front and the returned `Response` replays it.
+    pub(crate) async fn record_response(
+        &self,
+        method: Method,
+        url: String,
+        request_body: Vec<u8>,
+        response: Response,
+        proof: ProofHandle,
+    ) -> Result<(Response, ProofHandle), VerityError> {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let response_url = response.url().clone();
+        let body = response.bytes().await?;
+        let subject = rebuild_response(
+            status.as_u16(),
+            headers.clone(),
+            &response_url,
+            body.clone(),
+        )?;
+
+        let cassette = self.clone();
+        let cancellation_token = CancellationToken::new();
+        let cancelled = cancellation_token.clone();
+        let recorder = tokio::spawn(async move {
+            let proof = select! {
+                proof = proof => proof?,
+                () = cancelled.cancelled() => return Err(VerityError::ProofCancelled),
+            };
+
+            let interaction = Interaction::new(
+                &method,
+                &url,
+                &request_body,
+                status,
+                &headers,
+                &body,
+                &proof,
+            );
+            cassette.record_interaction(interaction)?;
+
+            Ok(proof)
+        });
+
+        Ok((subject, ProofHandle::new(recorder, cancellation_token)))
+    }
+
+    /// Appends an interaction and saves the cassette.
+    fn record_interaction(&self, interaction: Interaction) -> Result<(), VerityError> {
+        let mut tape = self.tape.lock().unwrap();
+        tape.interactions.push(interaction);
+        tape.replayed.push(false);
+
+        let content = serde_json::to_string_pretty(&tape.interactions).map_err(cassette_error)?;
+        fs::write(&self.path, content)
+            .map_err(|e| VerityError::Cassette(format!("{}: {}", self.path.display(), e)))
+    }
+}
+
+impl Interaction {
+    /// Captures an interaction. The response body must already be read, as `Response`
+    /// bodies can only be consumed once.
+    pub(crate) fn new(
+        method: &Method,
+        url: &str,
+        request_body: &[u8],
+        status: StatusCode,
+        headers: &HeaderMap,
+        body: &Bytes,
+        proof: &Proof,
+    ) -> Self {
+        Self {
+            method: method.to_string(),
+            url: url.to_string(),
+            request_body: hex::encode(request_body),
+            status: status.as_u16(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        name.to_string(),
+                        String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                    )
+                })
+                .collect(),
+            body: hex::encode(body),
+            notary_pub_key: proof.notary_pub_key.clone(),
+            proof: proof.proof.clone(),
+        }
+    }
+}
+
+/// Builds a `Response` from its recorded parts.
+pub(crate) fn rebuild_response(
+    status: u16,
+    headers: HeaderMap,
+    url: &Url,
+    body: Bytes,
+) -> Result<Response, VerityError> {
+    let mut response = http::Response::builder()
+        .status(StatusCode::from_u16(status).map_err(cassette_error)?)
+        .url(url.clone())
+        .body(body)
+        .map_err(cassette_error)?;
+    *response.headers_mut() = headers;
+
+    Ok(Response::from(response))
+}
+
+fn decode(body: &str) -> Result<Bytes, VerityError> {
+    Ok(hex::decode(body).map_err(cassette_error)?.into())
+}
+
+fn cassette_error(err: impl std::fmt::Display) -> VerityError {
+    VerityError::Cassette(err.to_string())
+}
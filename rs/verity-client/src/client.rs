@@ -1,26 +1,72 @@
-use std::str::FromStr;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
-use futures::stream::StreamExt;
+use futures::future::join_all;
+use futures::stream::{self, StreamExt};
 use http::{HeaderValue, Method};
-use reqwest::{IntoUrl, Response, Url};
-use reqwest_eventsource::{Event, EventSource};
-use serde::{Deserialize, Serialize};
+use reqwest::{IntoUrl, Response};
+use semver::VersionReq;
 use tokio::select;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
-use tracing::error;
-use uuid::Uuid;
-
+use tracing::{error, instrument, warn, Instrument, Span};
+use verity_proof_bundle::compression::{decode_proof, proof_encoding, ProofEncoding};
+pub use verity_proof_bundle::NotaryInformation;
+use verity_proof_bundle::ProofBundle;
+
+use crate::auth::ProverAuth;
+use crate::cassette::Cassette;
+use crate::error::VerityError;
+use crate::notary::{
+    check_notary_key, check_notary_version, TrustedNotaryKey, SUPPORTED_NOTARY_VERSIONS,
+};
+use crate::proof::{Proof, ProofHandle, ProvenRequest};
+use crate::prover::{ProverPool, ProverSelection, ProverStatus};
 use crate::request::RequestBuilder;
+use crate::store::ProofStore;
+use crate::telemetry;
+use crate::transport::{receive_proof_message, ProofTransport};
 
-/// Time to wait for a proof received over SSE connection since receiving HTTP response
+/// Time to wait for a proof since receiving HTTP response
 const PROOF_TIMEOUT: Duration = Duration::from_millis(30000); // default to 30 seconds.
 
+/// How long prover health checks stay fresh when several provers are configured
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Time a prover has to answer a health check before it is considered unhealthy
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long notary information is reused before it is fetched again
+const NOTARY_INFO_TTL: Duration = Duration::from_secs(300);
+
 #[derive(Clone)]
 pub struct VerityClientConfig {
     pub prover_url: String,
+    /// Further provers, used according to `prover_selection` together with `prover_url`.
+    pub additional_prover_urls: Vec<String>,
+    pub prover_selection: ProverSelection,
     pub proof_timeout: Option<Duration>,
+    /// How proofs are received from the prover.
+    pub proof_transport: ProofTransport,
+    /// Encodings the prover may compress proofs with. Proofs arrive uncompressed when empty.
+    pub accepted_proof_encodings: Vec<ProofEncoding>,
+    /// Encoding proofs are kept compressed with in responses and proof stores.
+    /// Proofs are decompressed on arrival when `None`.
+    pub proof_encoding: Option<ProofEncoding>,
+    /// Notary keys proofs must be signed with. Any key is accepted when empty.
+    pub trusted_notary_keys: Vec<TrustedNotaryKey>,
+    /// HTTP client used for every call to the prover. A default client is used when `None`.
+    pub http_client: Option<reqwest::Client>,
+    /// Credentials for an authenticating gateway in front of the prover.
+    pub prover_auth: Option<ProverAuth>,
+    /// Cassette requests are recorded to or replayed from.
+    pub cassette: Option<Cassette>,
+    /// Store every proof is persisted to as it arrives.
+    pub proof_store: Option<Arc<dyn ProofStore>>,
+    /// How long the notary information of a prover is reused before it is fetched again.
+    pub notary_info_ttl: Duration,
+    /// Requirements the notary version of a prover must satisfy. Not checked when empty.
+    pub notary_versions: Vec<VersionReq>,
 }
 
 impl VerityClientConfig {
@@ -28,45 +74,203 @@ impl VerityClientConfig {
     pub fn new(prover_url: impl Into<String>) -> Self {
         Self {
             prover_url: prover_url.into(),
+            additional_prover_urls: Vec::new(),
+            prover_selection: ProverSelection::default(),
             proof_timeout: None,
+            proof_transport: ProofTransport::default(),
+            accepted_proof_encodings: Vec::new(),
+            proof_encoding: None,
+            trusted_notary_keys: Vec::new(),
+            http_client: None,
+            prover_auth: None,
+            cassette: None,
+            proof_store: None,
+            notary_info_ttl: NOTARY_INFO_TTL,
+            notary_versions: Vec::new(),
         }
     }
 
+    /// Add a prover to send requests to. May be called repeatedly to add several provers.
+    pub fn with_additional_prover(mut self, prover_url: impl Into<String>) -> Self {
+        self.additional_prover_urls.push(prover_url.into());
+        self
+    }
+
+    /// Set how a prover is picked for each request when several are configured.
+    pub fn with_prover_selection(mut self, selection: ProverSelection) -> Self {
+        self.prover_selection = selection;
+        self
+    }
+
     /// Set a custom proof timeout.
     pub fn with_proof_timeout(mut self, timeout: Duration) -> Self {
         self.proof_timeout = Some(timeout);
         self
     }
+
+    /// Set how proofs are received from the prover. Defaults to server-sent events.
+    pub fn with_proof_transport(mut self, transport: ProofTransport) -> Self {
+        self.proof_transport = transport;
+        self
+    }
+
+    /// Let the prover compress proofs with `encoding` to save bandwidth. May be called
+    /// repeatedly; earlier encodings are preferred.
+    ///
+    /// Compressed proofs are decompressed on arrival unless `with_compressed_proofs`
+    /// is set.
+    pub fn with_accepted_proof_encoding(mut self, encoding: ProofEncoding) -> Self {
+        if !self.accepted_proof_encodings.contains(&encoding) {
+            self.accepted_proof_encodings.push(encoding);
+        }
+        self
+    }
+
+    /// Keep proofs compressed with `encoding` in `VerityResponse::proof` and in the proof
+    /// store, in the text form of `verity_proof_bundle::compression`. The prover is also
+    /// offered to send proofs with `encoding`, so they are not recompressed on arrival.
+    ///
+    /// `VerityResponse::verify` and `verity_verify_tls::verify_bundle` decompress such
+    /// proofs themselves. Other consumers can use `ProofBundle::proof_json`.
+    pub fn with_compressed_proofs(mut self, encoding: ProofEncoding) -> Self {
+        self.proof_encoding = Some(encoding);
+        self.with_accepted_proof_encoding(encoding)
+    }
+
+    /// Only accept proofs signed by `key`. May be called repeatedly to trust several keys.
+    pub fn with_trusted_notary_key(mut self, key: TrustedNotaryKey) -> Self {
+        self.trusted_notary_keys.push(key);
+        self
+    }
+
+    /// Use a preconfigured `reqwest::Client` for the `/proxy`, `/proof` and `/notaryinfo`
+    /// calls, e.g. to set a proxy, root certificates, timeouts or a user agent.
+    ///
+    /// The proof stream is a long-lived request, so a total request `timeout` on the
+    /// client should exceed the proof timeout. Prefer `connect_timeout` and
+    /// `read_timeout` to bound stalled connections.
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    /// Authenticate every call to the prover with `auth`.
+    pub fn with_prover_auth(mut self, auth: ProverAuth) -> Self {
+        self.prover_auth = Some(auth);
+        self
+    }
+
+    /// Record requests to, or replay them from, `cassette`.
+    pub fn with_cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Persist every proof to `store` as it arrives from the prover.
+    ///
+    /// Proofs are stored before they are handed to the caller, and a proof that cannot
+    /// be stored is reported as an error. Proofs replayed from a cassette are not stored.
+    pub fn with_proof_store(mut self, store: impl ProofStore + 'static) -> Self {
+        self.proof_store = Some(Arc::new(store));
+        self
+    }
+
+    /// Set how long notary information is reused before it is fetched again. Defaults
+    /// to five minutes; a zero TTL fetches it on every use.
+    pub fn with_notary_info_ttl(mut self, ttl: Duration) -> Self {
+        self.notary_info_ttl = ttl;
+        self
+    }
+
+    /// Only use provers whose notary version satisfies `requirement`. May be called
+    /// repeatedly; every requirement must be satisfied.
+    pub fn with_notary_version_req(mut self, requirement: VersionReq) -> Self {
+        self.notary_versions.push(requirement);
+        self
+    }
+
+    /// Only use provers whose notary version is supported by this client and, with the
+    /// `verify` feature, by the proof format `verity-verify-tls` is built for.
+    ///
+    /// The notary version is checked before a request is sent to a prover, so an
+    /// incompatible prover fails fast with `VerityError::IncompatibleNotary` instead of
+    /// producing proofs that cannot be verified.
+    pub fn with_notary_compatibility_check(self) -> Self {
+        let config = self.with_notary_version_req(
+            VersionReq::parse(SUPPORTED_NOTARY_VERSIONS).expect("valid notary version range"),
+        );
+
+        #[cfg(feature = "verify")]
+        let config = config.with_notary_version_req(
+            VersionReq::parse(verity_verify_tls::SUPPORTED_NOTARY_VERSIONS)
+                .expect("valid notary version range"),
+        );
+
+        config
+    }
 }
 
 #[derive(Clone)]
 pub struct VerityClient {
     pub(crate) inner: reqwest::Client,
     pub(crate) config: VerityClientConfig,
+    pub(crate) provers: Arc<ProverPool>,
 }
 
 pub struct VerityResponse {
     pub subject: Response,
     pub proof: String,
     pub notary_pub_key: String,
+    /// The request the proof was produced for.
+    pub request: ProvenRequest,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct NotaryInformation {
-    pub version: String,
-    pub public_key: String,
-    pub git_commit_hash: String,
-    pub git_commit_timestamp: String,
+impl VerityResponse {
+    /// Packs the proof, its notary key and the proven request into a `ProofBundle`,
+    /// which can be stored and later verified with `verity-verify-tls` or
+    /// `verity-verify-remote`.
+    ///
+    /// The bundle carries no `NotaryInformation`; add it with
+    /// `ProofBundle::with_notary_info()` if needed.
+    pub fn to_bundle(&self) -> ProofBundle {
+        self.request.bundle(&self.proof, &self.notary_pub_key)
+    }
 }
 
 impl VerityClient {
     /// Creates a new `VerityClient` with the given configuration.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if no HTTP client is configured and a default one cannot be
+    /// built, like `reqwest::Client::new()`, or if a client certificate is combined with
+    /// `ProofTransport::WebSocket`, which cannot present it.
     pub fn new(config: VerityClientConfig) -> Self {
-        return Self {
-            inner: reqwest::Client::new(),
-            config,
+        assert!(
+            !(config.proof_transport == ProofTransport::WebSocket
+                && matches!(config.prover_auth, Some(ProverAuth::ClientCertificate(_)))),
+            "ProofTransport::WebSocket cannot present a client certificate, use another transport"
+        );
+
+        let inner = match (&config.http_client, &config.prover_auth) {
+            (Some(http_client), _) => http_client.clone(),
+            (None, Some(ProverAuth::ClientCertificate(identity))) => reqwest::Client::builder()
+                .identity(identity.clone())
+                .build()
+                .expect("Client::builder() with a client certificate"),
+            (None, _) => reqwest::Client::new(),
         };
+
+        let prover_urls = std::iter::once(config.prover_url.clone())
+            .chain(config.additional_prover_urls.iter().cloned())
+            .collect();
+        let provers = Arc::new(ProverPool::new(prover_urls, config.prover_selection));
+
+        Self {
+            inner,
+            config,
+            provers,
+        }
     }
 
     /// Convenience method to make a `GET` request to a URL.
@@ -99,9 +303,47 @@ impl VerityClient {
         RequestBuilder {
             client: self.clone(),
             inner: self.inner.request(method, url),
+            redactions: Vec::new(),
         }
     }
 
+    /// Sends all `requests` concurrently, with at most `concurrency` of them in flight.
+    ///
+    /// Results are returned in input order, one per request, so a single failure does
+    /// not abort the batch. Each request keeps its own proof timeout, see
+    /// [`RequestBuilder::proof_timeout()`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use verity_client::client::{VerityClient, VerityClientConfig};
+    /// # async fn run() {
+    /// let client = VerityClient::new(VerityClientConfig::new("http://127.0.0.1:8080"));
+    ///
+    /// let requests = (1..=100)
+    ///     .map(|id| client.get(format!("https://jsonplaceholder.typicode.com/posts/{}", id)))
+    ///     .collect();
+    ///
+    /// for result in client.send_all(requests, 8).await {
+    ///     match result {
+    ///         Ok(response) => println!("proof: {} bytes", response.proof.len()),
+    ///         Err(err) => eprintln!("request failed: {}", err),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub async fn send_all(
+        &self,
+        requests: Vec<RequestBuilder>,
+        concurrency: usize,
+    ) -> Vec<Result<VerityResponse, VerityError>> {
+        stream::iter(requests)
+            .map(RequestBuilder::send)
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
     /// Executes a `Request` and returns a `VerityResponse`.
     ///
     /// A `Request` can be built manually with `Request::new()` or obtained
@@ -114,7 +356,10 @@ impl VerityClient {
     ///
     /// This method fails if there was an error while sending the request,
     /// a redirect loop was detected, or the redirect limit was exhausted.
-    pub async fn execute(&mut self, request: reqwest::Request) -> anyhow::Result<VerityResponse> {
+    pub async fn execute(
+        &mut self,
+        request: reqwest::Request,
+    ) -> Result<VerityResponse, VerityError> {
         self.execute_request(request).await
     }
 
@@ -126,46 +371,152 @@ impl VerityClient {
     pub async fn execute_request(
         &mut self,
         mut req: reqwest::Request,
-    ) -> anyhow::Result<VerityResponse> {
+    ) -> Result<VerityResponse, VerityError> {
+        let mut request = ProvenRequest::from_request(&mut req)?;
+
+        let (subject, proof) = self.execute_detached(req).await?;
+        let Proof {
+            notary_pub_key,
+            proof,
+        } = proof.await?;
+        request.proven_at = SystemTime::now();
+
+        Ok(VerityResponse {
+            subject,
+            proof,
+            notary_pub_key,
+            request,
+        })
+    }
+
+    /// Executes the given request and returns the response as soon as the prover
+    /// relays it, together with a `ProofHandle` resolving to the proof of execution.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the request cannot be sent. Failures to obtain the proof
+    /// are reported by the returned `ProofHandle`.
+    ///
+    /// When several provers are configured, a prover that cannot be connected to is
+    /// skipped in favour of the next one, as long as the request body can be replayed.
+    pub async fn execute_detached(
+        &mut self,
+        req: reqwest::Request,
+    ) -> Result<(Response, ProofHandle), VerityError> {
+        let Some(cassette) = &self.config.cassette else {
+            return self.execute_live(req).await;
+        };
+
+        let method = req.method().clone();
+        let url = req.url().to_string();
+        let request_body = req
+            .body()
+            .and_then(|body| body.as_bytes())
+            .unwrap_or_default()
+            .to_vec();
+
+        if cassette.is_replay() {
+            return cassette.replay_request(
+                &method,
+                &url,
+                &request_body,
+                &self.config.trusted_notary_keys,
+            );
+        }
+
+        let cassette = cassette.clone();
+        let (response, proof) = self.execute_live(req).await?;
+        cassette
+            .record_response(method, url, request_body, response, proof)
+            .await
+    }
+
+    /// Executes the request through a prover, failing over between provers.
+    async fn execute_live(
+        &self,
+        mut req: reqwest::Request,
+    ) -> Result<(Response, ProofHandle), VerityError> {
+        let request = ProvenRequest::from_request(&mut req)?;
         let proxy_url = &String::from(req.url().as_str());
-        let headers = req.headers_mut();
+        req.headers_mut()
+            .append("T-PROXY-URL", HeaderValue::from_str(proxy_url)?);
 
-        let request_id = Uuid::new_v4();
-        headers.append(
-            "T-REQUEST-ID",
-            HeaderValue::from_str(&format!("{}", request_id))?,
-        );
+        if let Some(auth) = &self.config.prover_auth {
+            auth.apply(req.headers_mut())?;
+        }
 
-        headers.append("T-PROXY-URL", HeaderValue::from_str(proxy_url)?);
+        if self.provers.claim_health_check(HEALTH_CHECK_INTERVAL) {
+            self.check_provers().await;
+        }
 
-        *req.url_mut() = Url::from_str(&format!("{}/proxy", self.config.prover_url))?;
+        let mut pending = Some(req);
+        let mut candidates = self.provers.candidates().into_iter().peekable();
+        while let (Some(prover), Some(req)) = (candidates.next(), pending.take()) {
+            if candidates.peek().is_some() {
+                pending = req.try_clone();
+            }
+
+            match self.execute_on(prover, req, &request).await {
+                Err(VerityError::Transport(err)) if err.is_connect() => {
+                    self.provers.mark_unhealthy(prover);
+                    if pending.is_none() {
+                        return Err(err.into());
+                    }
+                    warn!("prover unreachable, failing over: {}", err);
+                }
+                result => return result,
+            }
+        }
+
+        Err(VerityError::Prover("no prover configured".to_string()))
+    }
+
+    /// Executes the request through the prover at index `prover`. The proof is
+    /// awaited from the same prover.
+    #[instrument(
+        name = "verity_request",
+        skip_all,
+        fields(
+            request_id = %request.request_id,
+            host = request.url.host_str().unwrap_or_default(),
+            prover = %self.provers.url(prover),
+        )
+    )]
+    async fn execute_on(
+        &self,
+        prover: usize,
+        mut req: reqwest::Request,
+        request: &ProvenRequest,
+    ) -> Result<(Response, ProofHandle), VerityError> {
+        if !self.config.notary_versions.is_empty() {
+            let notary_information = self.notary_info(prover).await?;
+            check_notary_version(&self.config.notary_versions, &notary_information.version)?;
+        }
+
+        *req.url_mut() = self.provers.endpoint(prover, "proxy")?;
 
         let req = reqwest::RequestBuilder::from_parts(self.inner.clone(), req);
 
         let request_cancellation_token = CancellationToken::new();
         let timeout_cancellation_token = CancellationToken::new();
+        let handle_cancellation_token = CancellationToken::new();
 
         let proof_awaiter = self.await_proof(
-            request_id.to_string(),
+            prover,
+            request.clone(),
             request_cancellation_token.clone(),
             timeout_cancellation_token.clone(),
+            handle_cancellation_token.clone(),
         )?;
+        let proof_handle = ProofHandle::new(proof_awaiter, handle_cancellation_token);
 
-        // prettier-ignore
-        let (response, proof_msg) = tokio::try_join!(
-            self.send_request(req, request_cancellation_token, timeout_cancellation_token),
-            proof_awaiter
-        )
-        .map_err(|e| anyhow::anyhow!("Failed to prove the request: {}", e))?;
+        let response =
+            self.send_request(req, request_cancellation_token, timeout_cancellation_token);
 
-        let subject = response?;
-        let (notary_pub_key, proof) = proof_msg?;
+        // A failure to reach the prover takes precedence over the state of the proof stream
+        let subject = response.await??;
 
-        Ok(VerityResponse {
-            subject,
-            proof,
-            notary_pub_key,
-        })
+        Ok((subject, proof_handle))
     }
 
     /// Sends the request and handles cancellation tokens.
@@ -176,15 +527,19 @@ impl VerityClient {
         request: reqwest::RequestBuilder,
         request_cancellation_token: CancellationToken,
         timeout_cancellation_token: CancellationToken,
-    ) -> JoinHandle<anyhow::Result<reqwest::Response>> {
+    ) -> JoinHandle<Result<reqwest::Response, VerityError>> {
         let proof_timeout = self.config.proof_timeout.unwrap_or(PROOF_TIMEOUT);
 
-        tokio::spawn(async move {
+        let sender = async move {
+            let sent_at = Instant::now();
             let result = request.send().await;
             let response = result.map_err(|e| {
                 error!("{}", e);
+                // Nothing will be proven, so stop listening for a proof
+                request_cancellation_token.cancel();
                 e
             })?;
+            telemetry::response_received(sent_at.elapsed(), response.status());
 
             // If T-PROOF-ID header has value, wait for the proof with the timeout,
             // otherwise stop waiting
@@ -200,11 +555,17 @@ impl VerityClient {
             }
 
             Ok(response)
-        })
+        };
+
+        tokio::spawn(sender.instrument(Span::current()))
     }
 
     /// Awaits proof of request execution.
     ///
+    /// The proof is received over the configured `ProofTransport`, which reconnects
+    /// with an exponential backoff whenever the connection drops. Reconnects stop once
+    /// the proof timeout elapses, the request is cancelled or the proof handle is cancelled.
+    ///
     /// Returns a `JoinHandle` that resolves to the proof or an error.
     ///
     /// # Errors
@@ -212,61 +573,217 @@ impl VerityClient {
     /// This method fails if the proof cannot be obtained.
     fn await_proof(
         &self,
-        request_id: String,
+        prover: usize,
+        proven_request: ProvenRequest,
         request_cancellation_token: CancellationToken,
         timeout_cancellation_token: CancellationToken,
-    ) -> anyhow::Result<JoinHandle<anyhow::Result<(String, String)>>> {
-        let url = Url::from_str(&format!("{}/proof/{}", self.config.prover_url, request_id))?;
-        let mut event_source = EventSource::get(url);
-
-        let awaiter = tokio::task::spawn(async move {
-            while let Some(event) = event_source.next().await {
-                match event {
-                    Ok(Event::Open) => {}
-                    Ok(Event::Message(message)) => {
-                        let parts: Vec<&str> = message.data.splitn(2, "|").collect();
-                        if parts.len() != 2 {
-                            anyhow::bail!("Invalid proof response");
-                        }
-
-                        return Ok((parts[0].to_string(), parts[1].to_string()));
-                    }
-                    Err(err) => {
-                        error!("{}", err);
-                        Err(err)?;
-                    }
-                }
+        handle_cancellation_token: CancellationToken,
+    ) -> Result<JoinHandle<Result<Proof, VerityError>>, VerityError> {
+        let url = self
+            .provers
+            .endpoint(prover, &format!("proof/{}", proven_request.request_id))?;
+        let http_client = self.inner.clone();
+        let transport = self.config.proof_transport;
+        let accepted_encodings = self.config.accepted_proof_encodings.clone();
+        let encoding = self.config.proof_encoding;
+        let trusted_notary_keys = self.config.trusted_notary_keys.clone();
+        let prover_auth = self.config.prover_auth.clone();
+        let proof_store = self.config.proof_store.clone();
+        let span = Span::current();
+        let started_at = Instant::now();
+
+        let awaiter = async move {
+            let message = receive_proof_message(
+                transport,
+                &http_client,
+                url,
+                prover_auth.as_ref(),
+                &accepted_encodings,
+            )
+            .await?;
+
+            let parts: Vec<&str> = message.splitn(2, "|").collect();
+            if parts.len() != 2 {
+                return Err(VerityError::InvalidProofMessage);
             }
 
-            Ok((String::from(""), String::from("")))
-        });
+            check_notary_key(&trusted_notary_keys, parts[0])?;
+
+            let proof = Proof {
+                notary_pub_key: parts[0].to_string(),
+                proof: reencode_proof(parts[1], encoding)?,
+            };
+
+            if let Some(store) = proof_store {
+                let proven_request = ProvenRequest {
+                    proven_at: SystemTime::now(),
+                    ..proven_request
+                };
+                let bundle = proven_request.bundle(&proof.proof, &proof.notary_pub_key);
+                // Stores write to disk, which must not block the runtime
+                tokio::task::spawn_blocking(move || store.put(&bundle)).await??;
+            }
+
+            Ok(proof)
+        };
+        let awaiter = tokio::task::spawn(awaiter.instrument(span.clone()));
+
+        let join_handle = async move {
+            let abort_handle = awaiter.abort_handle();
 
-        let join_handle = tokio::spawn(async move {
-            // Wait for either SSE message, timeout or cancellation
-            select! {
+            // Wait for either the proof, timeout or cancellation of the request or handle
+            let proof = select! {
                 proof = awaiter => {
-                    proof.unwrap()
+                    proof?
                 }
                 () = timeout_cancellation_token.cancelled() => {
-                    anyhow::bail!("Timeout reached while waiting for a proof")
+                    Err(VerityError::Timeout)
                 }
                 () = request_cancellation_token.cancelled() => {
-                    Ok((String::new(), String::new()))
+                    Ok(Proof::default())
+                }
+                () = handle_cancellation_token.cancelled() => {
+                    Err(VerityError::ProofCancelled)
                 }
+            };
+
+            // Stop reconnecting once the outcome is known
+            abort_handle.abort();
+
+            let latency = started_at.elapsed();
+            match &proof {
+                Ok(proof) if proof.proof.is_empty() => {}
+                Ok(proof) => telemetry::proof_received(latency, proof.proof.len()),
+                Err(VerityError::Timeout) => telemetry::proof_timed_out(latency),
+                Err(VerityError::ProofCancelled) => {}
+                Err(err) => telemetry::proof_failed(latency, err),
             }
-        });
 
-        Ok(join_handle)
+            proof
+        };
+
+        Ok(tokio::spawn(join_handle.instrument(span)))
     }
 
     /// Get the information of the connected notary
-    pub async fn get_notary_info(&self) -> anyhow::Result<NotaryInformation> {
-        let notary_info_url = format!("{}/notaryinfo", self.config.prover_url);
-        let notary_information = reqwest::get(notary_info_url)
-            .await?
-            .json::<NotaryInformation>()
-            .await?;
+    ///
+    /// The notary of the first prover that can be connected to is returned. The
+    /// information is cached for the configured notary info TTL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the notary key is not in the configured trust list, or if
+    /// the notary version does not satisfy the configured version requirements.
+    pub async fn get_notary_info(&self) -> Result<NotaryInformation, VerityError> {
+        let mut candidates = self.provers.candidates().into_iter().peekable();
+        while let Some(prover) = candidates.next() {
+            let notary_information = match self.notary_info(prover).await {
+                Err(VerityError::Transport(err)) if err.is_connect() => {
+                    self.provers.mark_unhealthy(prover);
+                    if candidates.peek().is_none() {
+                        return Err(err.into());
+                    }
+                    warn!("prover unreachable, failing over: {}", err);
+                    continue;
+                }
+                result => result?,
+            };
+
+            check_notary_key(
+                &self.config.trusted_notary_keys,
+                &notary_information.public_key,
+            )?;
+            check_notary_version(&self.config.notary_versions, &notary_information.version)?;
+
+            return Ok(notary_information);
+        }
+
+        Err(VerityError::Prover("no prover configured".to_string()))
+    }
+
+    /// Health-checks every configured prover through `/notaryinfo` and returns their
+    /// status.
+    ///
+    /// With several provers configured, checks also run automatically before a request
+    /// once the previous results are older than 30 seconds.
+    pub async fn check_provers(&self) -> Vec<ProverStatus> {
+        let checks = (0..self.provers.len()).map(|prover| async move {
+            let started = Instant::now();
+            let result = tokio::time::timeout(HEALTH_CHECK_TIMEOUT, self.fetch_notary_info(prover));
+            match result.await {
+                Ok(Ok(notary_information)) => {
+                    self.provers.record_health(prover, Some(started.elapsed()));
+                    self.provers.cache_notary_info(prover, notary_information);
+                }
+                Ok(Err(err)) => {
+                    warn!("prover health check failed: {}", err);
+                    self.provers.record_health(prover, None);
+                }
+                Err(_) => {
+                    warn!("prover health check timed out");
+                    self.provers.record_health(prover, None);
+                }
+            }
+        });
+        join_all(checks).await;
+
+        self.provers.status()
+    }
+
+    /// Returns the notary information of the prover at index `prover`, reusing the
+    /// last fetched information until the notary info TTL elapses.
+    async fn notary_info(&self, prover: usize) -> Result<NotaryInformation, VerityError> {
+        let ttl = self.config.notary_info_ttl;
+        if let Some(notary_information) = self.provers.cached_notary_info(prover, ttl) {
+            return Ok(notary_information);
+        }
+
+        let notary_information = self.fetch_notary_info(prover).await?;
+        self.provers
+            .cache_notary_info(prover, notary_information.clone());
 
         Ok(notary_information)
     }
+
+    /// Fetches the notary information of the prover at index `prover`.
+    async fn fetch_notary_info(&self, prover: usize) -> Result<NotaryInformation, VerityError> {
+        let notary_info_url = self.provers.endpoint(prover, "notaryinfo")?;
+        let notary_information = authorize(
+            self.inner.get(notary_info_url),
+            self.config.prover_auth.as_ref(),
+        )?
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<NotaryInformation>()
+        .await?;
+
+        Ok(notary_information)
+    }
+}
+
+/// Brings a proof as delivered by the prover into the form proofs are kept in:
+/// compressed with `encoding`, or uncompressed when `encoding` is `None`.
+fn reencode_proof(proof: &str, encoding: Option<ProofEncoding>) -> Result<String, VerityError> {
+    let compression_error =
+        |e: verity_proof_bundle::BundleError| VerityError::Compression(e.to_string());
+
+    match encoding {
+        Some(encoding) if proof_encoding(proof) == Some(encoding) => Ok(proof.to_string()),
+        Some(encoding) => encoding
+            .encode(&decode_proof(proof).map_err(compression_error)?)
+            .map_err(compression_error),
+        None => Ok(decode_proof(proof).map_err(compression_error)?.into_owned()),
+    }
+}
+
+/// Attaches the prover credentials, if any, to a request for a prover endpoint.
+pub(crate) fn authorize(
+    request: reqwest::RequestBuilder,
+    auth: Option<&ProverAuth>,
+) -> Result<reqwest::RequestBuilder, VerityError> {
+    match auth.map(ProverAuth::header_pair).transpose()?.flatten() {
+        Some((name, value)) => Ok(request.header(name, value)),
+        None => Ok(request),
+    }
 }
@@ -0,0 +1,94 @@
+use http::header::InvalidHeaderValue;
+use thiserror::Error;
+use tokio::task::JoinError;
+
+/// Errors returned by the Verity client.
+#[derive(Debug, Error)]
+pub enum VerityError {
+    /// The prover could not be reached, or the HTTP exchange with it failed.
+    #[error("prover transport error: {0}")]
+    Transport(#[from] reqwest::Error),
+
+    /// The prover is misconfigured or answered with something unexpected.
+    #[error("prover error: {0}")]
+    Prover(String),
+
+    /// The proof event stream failed.
+    #[error("proof stream error: {0}")]
+    Sse(Box<reqwest_eventsource::Error>),
+
+    /// The proof WebSocket could not be opened.
+    #[error("proof socket error: {0}")]
+    WebSocket(Box<tokio_tungstenite::tungstenite::Error>),
+
+    /// No proof arrived within the configured proof timeout.
+    #[error("timeout reached while waiting for a proof")]
+    Timeout,
+
+    /// The caller cancelled the `ProofHandle` before the proof arrived.
+    #[error("waiting for the proof was cancelled")]
+    ProofCancelled,
+
+    /// The prover sent a proof message that is not in the `pubkey|proof` format.
+    #[error("invalid proof response")]
+    InvalidProofMessage,
+
+    /// A proof could not be compressed or decompressed.
+    #[error("proof compression error: {0}")]
+    Compression(String),
+
+    /// The proof or notary information is signed by a key outside the trusted set.
+    #[error("untrusted notary public key: {0}")]
+    UntrustedNotary(String),
+
+    /// The notary of the prover runs a version this client cannot work with.
+    #[error("notary version {version} is not supported, expected {supported}")]
+    IncompatibleNotary { version: String, supported: String },
+
+    /// A redaction rule is malformed.
+    #[error("invalid redaction: {0}")]
+    InvalidRedaction(String),
+
+    /// The response was not proven, so there is nothing to verify.
+    #[cfg(feature = "verify")]
+    #[error("response carries no proof")]
+    MissingProof,
+
+    /// The proof failed local verification.
+    #[cfg(feature = "verify")]
+    #[error("proof verification failed: {0}")]
+    Verification(#[from] verity_verify_tls::VerifyTlsError),
+
+    /// The response received by the client differs from the notarized response.
+    #[cfg(feature = "verify")]
+    #[error("response does not match the notarized transcript: {0}")]
+    TranscriptMismatch(String),
+
+    /// A cassette could not be read or written, or has no matching interaction.
+    #[error("cassette error: {0}")]
+    Cassette(String),
+
+    /// A proof store could not be read or written.
+    #[error("proof store error: {0}")]
+    Store(String),
+
+    /// A value could not be encoded as an HTTP header.
+    #[error("invalid header value: {0}")]
+    HeaderEncoding(#[from] InvalidHeaderValue),
+
+    /// A background task driving the request panicked or was aborted.
+    #[error("request task failed: {0}")]
+    Task(#[from] JoinError),
+}
+
+impl From<reqwest_eventsource::Error> for VerityError {
+    fn from(err: reqwest_eventsource::Error) -> Self {
+        VerityError::Sse(Box::new(err))
+    }
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for VerityError {
+    fn from(err: tokio_tungstenite::tungstenite::Error) -> Self {
+        VerityError::WebSocket(Box::new(err))
+    }
+}
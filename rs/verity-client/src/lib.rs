@@ -1,2 +1,26 @@
+pub mod auth;
+pub mod blocking;
+pub mod cassette;
 pub mod client;
+pub mod error;
+pub mod notary;
+pub mod proof;
+pub mod prover;
+pub mod redaction;
 pub mod request;
+pub mod store;
+pub mod telemetry;
+pub mod transport;
+#[cfg(feature = "verify")]
+pub mod verify;
+
+pub use auth::ProverAuth;
+pub use error::VerityError;
+pub use proof::{Proof, ProofHandle, ProvenRequest};
+pub use prover::{ProverSelection, ProverStatus};
+pub use redaction::Redaction;
+pub use semver::VersionReq;
+pub use store::{ProofQuery, ProofStore};
+pub use transport::ProofTransport;
+pub use verity_proof_bundle::compression::ProofEncoding;
+pub use verity_proof_bundle::ProofBundle;
@@ -0,0 +1,172 @@
+use std::time::SystemTime;
+
+use semver::{Version, VersionReq};
+
+use crate::error::VerityError;
+
+/// Notary versions this client can prove requests with, as a semver requirement.
+pub const SUPPORTED_NOTARY_VERSIONS: &str = ">=0.1.0-alpha.6, <0.2.0";
+
+/// A notary public key the client accepts proofs from.
+///
+/// Keys are PEM encoded, as published by the prover on `/notaryinfo`. A key can be
+/// restricted to a validity window, which is useful when rotating notary keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustedNotaryKey {
+    pub public_key: String,
+    pub not_before: Option<SystemTime>,
+    pub not_after: Option<SystemTime>,
+}
+
+impl TrustedNotaryKey {
+    /// Trust a PEM encoded notary public key without time restrictions.
+    pub fn new(public_key: impl Into<String>) -> Self {
+        Self {
+            public_key: public_key.into(),
+            not_before: None,
+            not_after: None,
+        }
+    }
+
+    /// Only trust the key from the given time onwards.
+    pub fn valid_from(mut self, not_before: SystemTime) -> Self {
+        self.not_before = Some(not_before);
+        self
+    }
+
+    /// Stop trusting the key after the given time.
+    pub fn valid_until(mut self, not_after: SystemTime) -> Self {
+        self.not_after = Some(not_after);
+        self
+    }
+
+    /// Whether this entry trusts `public_key` at time `at`.
+    pub fn trusts(&self, public_key: &str, at: SystemTime) -> bool {
+        let started = self.not_before.is_none_or(|not_before| at >= not_before);
+        let expired = self.not_after.is_some_and(|not_after| at > not_after);
+
+        started && !expired && normalize_pem(&self.public_key) == normalize_pem(public_key)
+    }
+}
+
+/// Checks `public_key` against a list of trusted notary keys.
+///
+/// An empty list trusts every key, which preserves the behaviour of clients that
+/// do not pin notary keys.
+///
+/// # Errors
+///
+/// This method fails if the list is not empty and no entry trusts the key now.
+pub(crate) fn check_notary_key(
+    trusted: &[TrustedNotaryKey],
+    public_key: &str,
+) -> Result<(), VerityError> {
+    let now = SystemTime::now();
+
+    if trusted.is_empty() || trusted.iter().any(|key| key.trusts(public_key, now)) {
+        Ok(())
+    } else {
+        Err(VerityError::UntrustedNotary(public_key.to_string()))
+    }
+}
+
+/// Checks the version a notary reports against a list of version requirements.
+///
+/// An empty list accepts every version. Otherwise the version must be valid semver,
+/// optionally prefixed with `v`, and satisfy every requirement.
+///
+/// # Errors
+///
+/// This method fails if the version is not valid semver or misses a requirement.
+pub(crate) fn check_notary_version(
+    requirements: &[VersionReq],
+    version: &str,
+) -> Result<(), VerityError> {
+    if requirements.is_empty() {
+        return Ok(());
+    }
+
+    let compatible = Version::parse(version.trim_start_matches('v'))
+        .is_ok_and(|parsed| requirements.iter().all(|req| req.matches(&parsed)));
+    if compatible {
+        return Ok(());
+    }
+
+    Err(VerityError::IncompatibleNotary {
+        version: version.to_string(),
+        supported: requirements
+            .iter()
+            .map(VersionReq::to_string)
+            .collect::<Vec<_>>()
+            .join(" and "),
+    })
+}
+
+/// Reduces a PEM document to its significant characters.
+///
+/// Provers may deliver keys with escaped (`\n`) or platform specific line endings.
+fn normalize_pem(pem: &str) -> String {
+    pem.replace("\\n", "\n")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    const KEY: &str = "-----BEGIN PUBLIC KEY-----\nMFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE\n-----END PUBLIC KEY-----\n";
+
+    #[test]
+    fn matches_keys_regardless_of_line_endings() {
+        let escaped = KEY.replace('\n', "\\n");
+        let crlf = KEY.replace('\n', "\r\n");
+        let trusted = [TrustedNotaryKey::new(KEY)];
+
+        assert!(check_notary_key(&trusted, &escaped).is_ok());
+        assert!(check_notary_key(&trusted, &crlf).is_ok());
+        assert!(check_notary_key(&trusted, "-----BEGIN PUBLIC KEY-----other").is_err());
+    }
+
+    #[test]
+    fn respects_validity_window() {
+        let now = SystemTime::now();
+        let hour = Duration::from_secs(3600);
+
+        let expired = TrustedNotaryKey::new(KEY).valid_until(now - hour);
+        let pending = TrustedNotaryKey::new(KEY).valid_from(now + hour);
+        let current = TrustedNotaryKey::new(KEY)
+            .valid_from(now - hour)
+            .valid_until(now + hour);
+
+        assert!(!expired.trusts(KEY, now));
+        assert!(!pending.trusts(KEY, now));
+        assert!(current.trusts(KEY, now));
+    }
+
+    #[test]
+    fn empty_trust_list_trusts_any_key() {
+        assert!(check_notary_key(&[], KEY).is_ok());
+    }
+
+    #[test]
+    fn checks_notary_versions_against_every_requirement() {
+        let supported = [
+            VersionReq::parse(SUPPORTED_NOTARY_VERSIONS).unwrap(),
+            VersionReq::parse("=0.1.0-alpha.6").unwrap(),
+        ];
+
+        assert!(check_notary_version(&supported, "0.1.0-alpha.6").is_ok());
+        assert!(check_notary_version(&supported, "v0.1.0-alpha.6").is_ok());
+        assert!(check_notary_version(&supported, "0.1.0-alpha.7").is_err());
+        assert!(check_notary_version(&supported[..1], "0.1.0-alpha.7").is_ok());
+        assert!(matches!(
+            check_notary_version(&supported, "test"),
+            Err(VerityError::IncompatibleNotary { version, .. }) if version == "test"
+        ));
+        assert!(check_notary_version(&[], "test").is_ok());
+    }
+}
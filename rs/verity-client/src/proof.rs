@@ -0,0 +1,148 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::SystemTime;
+
+use http::{HeaderMap, HeaderValue, Method};
+use reqwest::{Request, Url};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+use verity_proof_bundle::ProofBundle;
+
+use crate::error::VerityError;
+use crate::redaction::Redaction;
+
+/// A proof of request execution, as delivered by the prover.
+///
+/// Both fields are empty when the prover did not notarize the request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Proof {
+    pub notary_pub_key: String,
+    pub proof: String,
+}
+
+/// The request a proof was produced for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProvenRequest {
+    /// The `T-REQUEST-ID` the request was sent to the prover with.
+    pub request_id: String,
+    pub method: Method,
+    /// The target URL of the request.
+    pub url: Url,
+    /// The redaction rules the request was sent with.
+    pub redactions: Vec<Redaction>,
+    /// When the proof was received.
+    pub proven_at: SystemTime,
+}
+
+impl ProvenRequest {
+    /// Describes a request that is about to be sent to the prover, assigning it a
+    /// `T-REQUEST-ID` unless it already has a valid one.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the `T-REDACTED` header of the request is invalid.
+    pub(crate) fn from_request(req: &mut Request) -> Result<Self, VerityError> {
+        let request_id = assign_request_id(req.headers_mut())?;
+        let redactions = match req.headers().get("T-REDACTED") {
+            Some(value) => Redaction::parse_header_value(
+                value
+                    .to_str()
+                    .map_err(|e| VerityError::InvalidRedaction(e.to_string()))?,
+            )?,
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            request_id: request_id.to_string(),
+            method: req.method().clone(),
+            url: req.url().clone(),
+            redactions,
+            proven_at: SystemTime::now(),
+        })
+    }
+
+    /// Packs a proof of this request into a `ProofBundle`.
+    pub(crate) fn bundle(&self, proof: &str, notary_pub_key: &str) -> ProofBundle {
+        ProofBundle::new(proof, notary_pub_key)
+            .with_request(&self.request_id, self.method.as_str(), self.url.as_str())
+            .with_timestamp(self.proven_at)
+            .with_redactions(self.redactions.iter().map(Redaction::to_string).collect())
+    }
+}
+
+/// Returns the `T-REQUEST-ID` of a request, assigning a new one unless it already has
+/// a valid one, so that the id recorded with a proof is the one sent to the prover.
+fn assign_request_id(headers: &mut HeaderMap) -> Result<Uuid, VerityError> {
+    let existing = headers
+        .get("T-REQUEST-ID")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| Uuid::parse_str(value).ok());
+    if let Some(request_id) = existing {
+        return Ok(request_id);
+    }
+
+    let request_id = Uuid::new_v4();
+    headers.insert(
+        "T-REQUEST-ID",
+        HeaderValue::from_str(&request_id.to_string())?,
+    );
+
+    Ok(request_id)
+}
+
+/// A proof that is still being produced in the background.
+///
+/// The handle is a future resolving to the [`Proof`], so it can be awaited, polled
+/// alongside other work, or cancelled once the proof is no longer needed. Dropping
+/// the handle cancels it as well.
+pub struct ProofHandle {
+    task: JoinHandle<Result<Proof, VerityError>>,
+    cancellation_token: CancellationToken,
+}
+
+impl ProofHandle {
+    pub(crate) fn new(
+        task: JoinHandle<Result<Proof, VerityError>>,
+        cancellation_token: CancellationToken,
+    ) -> Self {
+        Self {
+            task,
+            cancellation_token,
+        }
+    }
+
+    /// A handle for a proof that is already known.
+    pub(crate) fn ready(proof: Proof) -> Self {
+        Self::new(
+            tokio::spawn(async move { Ok(proof) }),
+            CancellationToken::new(),
+        )
+    }
+
+    /// Stops waiting for the proof. Awaiting the handle afterwards yields
+    /// `VerityError::ProofCancelled`, unless the proof had already arrived.
+    pub fn cancel(&self) {
+        self.cancellation_token.cancel();
+    }
+
+    /// Returns `true` once the proof arrived or waiting for it failed.
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+impl Future for ProofHandle {
+    type Output = Result<Proof, VerityError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.task).poll(cx).map(|result| result?)
+    }
+}
+
+impl Drop for ProofHandle {
+    fn drop(&mut self) {
+        self.cancellation_token.cancel();
+    }
+}
@@ -0,0 +1,196 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use reqwest::Url;
+
+use crate::client::NotaryInformation;
+use crate::error::VerityError;
+
+/// How the client picks a prover for each request when several are configured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProverSelection {
+    /// Always prefer provers in configuration order, falling back to the next one.
+    #[default]
+    PriorityFailover,
+    /// Spread requests evenly over all healthy provers.
+    RoundRobin,
+    /// Prefer the prover with the lowest `/notaryinfo` latency.
+    LeastLatency,
+}
+
+/// The last known state of a prover.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProverStatus {
+    pub url: String,
+    pub healthy: bool,
+    /// Round trip time of the last successful health check.
+    pub latency: Option<Duration>,
+}
+
+/// The provers known to a client, shared by all of its clones.
+pub(crate) struct ProverPool {
+    selection: ProverSelection,
+    next: AtomicUsize,
+    status: Mutex<Vec<ProverStatus>>,
+    last_health_check: Mutex<Option<Instant>>,
+    /// The last notary information fetched from each prover, and when it was fetched.
+    notary_info: Mutex<Vec<Option<(NotaryInformation, Instant)>>>,
+}
+
+impl ProverPool {
+    pub(crate) fn new(urls: Vec<String>, selection: ProverSelection) -> Self {
+        let notary_info = vec![None; urls.len()];
+        let status = urls
+            .into_iter()
+            .map(|url| ProverStatus {
+                url,
+                healthy: true,
+                latency: None,
+            })
+            .collect();
+
+        Self {
+            selection,
+            next: AtomicUsize::new(0),
+            status: Mutex::new(status),
+            last_health_check: Mutex::new(None),
+            notary_info: Mutex::new(notary_info),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.status().len()
+    }
+
+    pub(crate) fn status(&self) -> Vec<ProverStatus> {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Returns the indices of all provers in the order they should be tried.
+    ///
+    /// Unhealthy provers are kept as a last resort, as they may have recovered since
+    /// they were last checked.
+    pub(crate) fn candidates(&self) -> Vec<usize> {
+        let status = self.status();
+        let mut order: Vec<usize> = (0..status.len()).collect();
+
+        match self.selection {
+            ProverSelection::PriorityFailover => {}
+            ProverSelection::RoundRobin => {
+                let start = self.next.fetch_add(1, Ordering::Relaxed) % status.len();
+                order.rotate_left(start);
+            }
+            ProverSelection::LeastLatency => {
+                order.sort_by_key(|&index| status[index].latency.unwrap_or(Duration::MAX));
+            }
+        }
+
+        // Stable, so the strategy's order is kept within each group
+        order.sort_by_key(|&index| !status[index].healthy);
+        order
+    }
+
+    /// The URL of the prover at `index`.
+    pub(crate) fn url(&self, index: usize) -> String {
+        self.status.lock().unwrap()[index].url.clone()
+    }
+
+    /// Resolves `path` against the URL of the prover at `index`.
+    pub(crate) fn endpoint(&self, index: usize, path: &str) -> Result<Url, VerityError> {
+        let endpoint = format!("{}/{}", self.status.lock().unwrap()[index].url, path);
+        Url::parse(&endpoint)
+            .map_err(|e| VerityError::Prover(format!("invalid prover url {}: {}", endpoint, e)))
+    }
+
+    pub(crate) fn mark_unhealthy(&self, index: usize) {
+        self.status.lock().unwrap()[index].healthy = false;
+    }
+
+    pub(crate) fn record_health(&self, index: usize, latency: Option<Duration>) {
+        let mut status = self.status.lock().unwrap();
+        status[index].healthy = latency.is_some();
+        if latency.is_some() {
+            status[index].latency = latency;
+        }
+    }
+
+    /// The notary information of the prover at `index`, if it was fetched less than
+    /// `ttl` ago.
+    pub(crate) fn cached_notary_info(
+        &self,
+        index: usize,
+        ttl: Duration,
+    ) -> Option<NotaryInformation> {
+        match &self.notary_info.lock().unwrap()[index] {
+            Some((info, fetched)) if fetched.elapsed() < ttl => Some(info.clone()),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn cache_notary_info(&self, index: usize, info: NotaryInformation) {
+        self.notary_info.lock().unwrap()[index] = Some((info, Instant::now()));
+    }
+
+    /// Whether the provers should be health-checked before the next request.
+    ///
+    /// Claims the check, so concurrent requests do not all run it.
+    pub(crate) fn claim_health_check(&self, interval: Duration) -> bool {
+        if self.len() < 2 {
+            return false;
+        }
+
+        let mut last_health_check = self.last_health_check.lock().unwrap();
+        match *last_health_check {
+            Some(checked) if checked.elapsed() < interval => false,
+            _ => {
+                *last_health_check = Some(Instant::now());
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(selection: ProverSelection) -> ProverPool {
+        ProverPool::new(
+            vec!["http://a".into(), "http://b".into(), "http://c".into()],
+            selection,
+        )
+    }
+
+    #[test]
+    fn priority_failover_skips_unhealthy_provers() {
+        let pool = pool(ProverSelection::PriorityFailover);
+        assert_eq!(pool.candidates(), [0, 1, 2]);
+
+        pool.mark_unhealthy(0);
+        assert_eq!(pool.candidates(), [1, 2, 0]);
+    }
+
+    #[test]
+    fn round_robin_rotates_the_first_prover() {
+        let pool = pool(ProverSelection::RoundRobin);
+        let first: Vec<usize> = (0..4).map(|_| pool.candidates()[0]).collect();
+        assert_eq!(first, [0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn least_latency_prefers_the_fastest_healthy_prover() {
+        let pool = pool(ProverSelection::LeastLatency);
+        pool.record_health(0, Some(Duration::from_millis(30)));
+        pool.record_health(1, Some(Duration::from_millis(10)));
+        pool.record_health(2, None);
+
+        assert_eq!(pool.candidates(), [1, 0, 2]);
+    }
+
+    #[test]
+    fn single_prover_is_never_health_checked() {
+        let pool = ProverPool::new(vec!["http://a".into()], ProverSelection::RoundRobin);
+        assert!(!pool.claim_health_check(Duration::ZERO));
+    }
+}
@@ -0,0 +1,345 @@
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use http::HeaderName;
+use regex::Regex;
+
+use crate::error::VerityError;
+
+/// Separator between rules in the `T-REDACTED` header.
+const RULE_SEPARATOR: &str = ", ";
+
+/// The side of the HTTP exchange a redaction applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RedactionTarget {
+    /// The request sent to the target server (`req`).
+    Request,
+    /// The response returned by the target server (`res`).
+    Response,
+}
+
+impl RedactionTarget {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RedactionTarget::Request => "req",
+            RedactionTarget::Response => "res",
+        }
+    }
+}
+
+/// A rule instructing the Verity Prover to hide part of the notarized transcript.
+///
+/// Rules are serialized to the `T-REDACTED` header as `<target>:<kind>:<value>`,
+/// for example `req:header:authorization`, `res:body:data.token`, `res:regex:[0-9]+` or
+/// `res:range:0-16`.
+///
+/// Regex and range rules are only applied by provers that support them, such as
+/// `verity-mock-prover`. A prover that does not reveals the data they were meant to
+/// hide, so check the proof before relying on them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Redaction {
+    /// Hides the value of a header.
+    Header {
+        target: RedactionTarget,
+        name: String,
+    },
+    /// Hides the value at a dot-separated path of a JSON body.
+    JsonPath {
+        target: RedactionTarget,
+        path: String,
+    },
+    /// Hides every match of a regular expression in the body.
+    Regex {
+        target: RedactionTarget,
+        pattern: String,
+    },
+    /// Hides a range of bytes of the transcript.
+    Range {
+        target: RedactionTarget,
+        range: Range<usize>,
+    },
+}
+
+impl Redaction {
+    /// Hides the value of a request header, such as an API key.
+    pub fn request_header(name: impl Into<String>) -> Self {
+        Redaction::Header {
+            target: RedactionTarget::Request,
+            name: name.into(),
+        }
+    }
+
+    /// Hides the value of a response header, such as a session cookie.
+    pub fn response_header(name: impl Into<String>) -> Self {
+        Redaction::Header {
+            target: RedactionTarget::Response,
+            name: name.into(),
+        }
+    }
+
+    /// Hides the value at a JSON path of the request body.
+    pub fn request_json(path: impl Into<String>) -> Self {
+        Redaction::JsonPath {
+            target: RedactionTarget::Request,
+            path: path.into(),
+        }
+    }
+
+    /// Hides the value at a JSON path of the response body.
+    pub fn response_json(path: impl Into<String>) -> Self {
+        Redaction::JsonPath {
+            target: RedactionTarget::Response,
+            path: path.into(),
+        }
+    }
+
+    /// Hides every match of a regular expression in the request body.
+    pub fn request_regex(pattern: impl Into<String>) -> Self {
+        Redaction::Regex {
+            target: RedactionTarget::Request,
+            pattern: pattern.into(),
+        }
+    }
+
+    /// Hides every match of a regular expression in the response body.
+    pub fn response_regex(pattern: impl Into<String>) -> Self {
+        Redaction::Regex {
+            target: RedactionTarget::Response,
+            pattern: pattern.into(),
+        }
+    }
+
+    /// Hides every occurrence of a literal substring in the request body.
+    pub fn request_substring(substring: &str) -> Self {
+        Self::request_regex(regex::escape(substring))
+    }
+
+    /// Hides every occurrence of a literal substring in the response body.
+    pub fn response_substring(substring: &str) -> Self {
+        Self::response_regex(regex::escape(substring))
+    }
+
+    /// Hides a range of bytes of the request.
+    pub fn request_range(range: Range<usize>) -> Self {
+        Redaction::Range {
+            target: RedactionTarget::Request,
+            range,
+        }
+    }
+
+    /// Hides a range of bytes of the response.
+    pub fn response_range(range: Range<usize>) -> Self {
+        Redaction::Range {
+            target: RedactionTarget::Response,
+            range,
+        }
+    }
+
+    /// Checks that the rule can be understood by the Verity Prover.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if a header name, JSON path or pattern is malformed,
+    /// if a range is empty, or if a value contains the rule separator.
+    pub fn validate(&self) -> Result<(), VerityError> {
+        let invalid = |reason: String| Err(VerityError::InvalidRedaction(reason));
+
+        match self {
+            Redaction::Header { name, .. } => {
+                if HeaderName::from_str(name).is_err() {
+                    return invalid(format!("invalid header name {:?}", name));
+                }
+            }
+            Redaction::JsonPath { path, .. } => {
+                if path.split('.').any(|segment| segment.trim().is_empty()) {
+                    return invalid(format!("invalid JSON path {:?}", path));
+                }
+                if path.contains(',') {
+                    return invalid(format!("JSON path {:?} must not contain ','", path));
+                }
+            }
+            Redaction::Regex { pattern, .. } => {
+                if let Err(e) = Regex::new(pattern) {
+                    return invalid(format!("invalid pattern {:?}: {}", pattern, e));
+                }
+                if pattern.contains(',') {
+                    return invalid(format!("pattern {:?} must not contain ','", pattern));
+                }
+            }
+            Redaction::Range { range, .. } => {
+                if range.is_empty() {
+                    return invalid(format!("empty range {:?}", range));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Serializes a list of rules to the value of the `T-REDACTED` header.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if any of the rules is invalid.
+    pub fn to_header_value(redactions: &[Redaction]) -> Result<String, VerityError> {
+        redactions.iter().try_for_each(Redaction::validate)?;
+
+        Ok(redactions
+            .iter()
+            .map(Redaction::to_string)
+            .collect::<Vec<_>>()
+            .join(RULE_SEPARATOR))
+    }
+
+    /// Parses the value of a `T-REDACTED` header into a list of rules.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if any of the rules is invalid.
+    pub fn parse_header_value(value: &str) -> Result<Vec<Redaction>, VerityError> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+            .map(Redaction::from_str)
+            .collect()
+    }
+}
+
+impl fmt::Display for Redaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Redaction::Header { target, name } => {
+                write!(f, "{}:header:{}", target.as_str(), name)
+            }
+            Redaction::JsonPath { target, path } => {
+                write!(f, "{}:body:{}", target.as_str(), path)
+            }
+            Redaction::Regex { target, pattern } => {
+                write!(f, "{}:regex:{}", target.as_str(), pattern)
+            }
+            Redaction::Range { target, range } => {
+                write!(f, "{}:range:{}-{}", target.as_str(), range.start, range.end)
+            }
+        }
+    }
+}
+
+impl FromStr for Redaction {
+    type Err = VerityError;
+
+    /// Parses a single rule, such as `res:body:data.token`.
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let invalid = || VerityError::InvalidRedaction(format!("invalid rule {:?}", rule));
+
+        let mut parts = rule.trim().splitn(3, ':');
+        let (target, kind, value) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(target), Some(kind), Some(value)) => (target, kind, value),
+            _ => return Err(invalid()),
+        };
+
+        let target = match target {
+            "req" => RedactionTarget::Request,
+            "res" => RedactionTarget::Response,
+            _ => return Err(invalid()),
+        };
+
+        let redaction = match kind {
+            "header" => Redaction::Header {
+                target,
+                name: value.to_string(),
+            },
+            "body" => Redaction::JsonPath {
+                target,
+                path: value.to_string(),
+            },
+            "regex" => Redaction::Regex {
+                target,
+                pattern: value.to_string(),
+            },
+            "range" => {
+                let (start, end) = value.split_once('-').ok_or_else(invalid)?;
+                let start = start.parse().map_err(|_| invalid())?;
+                let end = end.parse().map_err(|_| invalid())?;
+                Redaction::Range {
+                    target,
+                    range: start..end,
+                }
+            }
+            _ => return Err(invalid()),
+        };
+
+        redaction.validate()?;
+
+        Ok(redaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_rules_to_header_value() {
+        let redactions = vec![
+            Redaction::request_header("authorization"),
+            Redaction::response_json("data.token"),
+            Redaction::response_substring("4111.1111"),
+            Redaction::response_range(10..20),
+        ];
+
+        let value = Redaction::to_header_value(&redactions).unwrap();
+        assert_eq!(
+            value,
+            r"req:header:authorization, res:body:data.token, res:regex:4111\.1111, res:range:10-20"
+        );
+        assert_eq!(Redaction::parse_header_value(&value).unwrap(), redactions);
+    }
+
+    #[test]
+    fn parses_legacy_rule_strings() {
+        let redactions =
+            Redaction::parse_header_value("req:body:firstName, res:body:firstName").unwrap();
+        assert_eq!(
+            redactions,
+            vec![
+                Redaction::request_json("firstName"),
+                Redaction::response_json("firstName"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        let invalid = [
+            Redaction::request_header("bad header"),
+            Redaction::response_json("data..token"),
+            Redaction::response_json("data.to,ken"),
+            Redaction::response_regex("("),
+            Redaction::response_regex(r"\d{1,3}"),
+            Redaction::response_range(5..5),
+        ];
+        for redaction in invalid {
+            assert!(
+                matches!(redaction.validate(), Err(VerityError::InvalidRedaction(_))),
+                "{:?} should be rejected",
+                redaction
+            );
+        }
+
+        for rule in [
+            "res:body",
+            "all:body:x",
+            "res:cookie:x",
+            "res:regex:(",
+            "res:range:10",
+        ] {
+            assert!(
+                rule.parse::<Redaction>().is_err(),
+                "{} should be rejected",
+                rule
+            );
+        }
+    }
+}
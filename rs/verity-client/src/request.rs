@@ -1,10 +1,14 @@
 use std::convert::TryFrom;
+use std::time::Duration;
 
 use http::{HeaderName, HeaderValue};
-use reqwest::{header::HeaderMap, Body, Request};
+use reqwest::{header::HeaderMap, Body, Request, Response};
 use serde::Serialize;
 
 use crate::client::{VerityClient, VerityResponse};
+use crate::error::VerityError;
+use crate::proof::ProofHandle;
+use crate::redaction::Redaction;
 
 /// A builder to construct the properties of a `Request`.
 ///
@@ -13,6 +17,7 @@ use crate::client::{VerityClient, VerityResponse};
 pub struct RequestBuilder {
     pub(crate) client: VerityClient,
     pub(crate) inner: reqwest::RequestBuilder,
+    pub(crate) redactions: Vec<Redaction>,
 }
 
 impl RequestBuilder {
@@ -80,22 +85,30 @@ impl RequestBuilder {
 
     /// Add a Redact instruction.
     ///
-    /// This method adds a header to instruct Verity Prover on how to hide sensitive data.
-    pub fn redact(self, redact: String) -> Self {
-        RequestBuilder {
-            inner: self
-                .inner
-                .header("T-REDACTED", HeaderValue::from_str(&redact).unwrap()),
-            ..self
-        }
+    /// This method instructs Verity Prover on how to hide sensitive data. It can be
+    /// called multiple times; all rules are sent together in the `T-REDACTED` header,
+    /// after any rules already set on that header directly.
+    pub fn redact(mut self, redaction: Redaction) -> Self {
+        self.redactions.push(redaction);
+        self
+    }
+
+    /// Override the client's proof timeout for this request only.
+    pub fn proof_timeout(mut self, timeout: Duration) -> Self {
+        self.client.config.proof_timeout = Some(timeout);
+        self
     }
 
     /// Build a `Request`.
     ///
     /// This method constructs the request, which can then be
     /// inspected, modified and executed with `VerityClient::execute()`.
-    pub fn build(self) -> reqwest::Result<Request> {
-        self.inner.build()
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the request or any of its redaction rules is invalid.
+    pub fn build(self) -> Result<Request, VerityError> {
+        self.build_split().1
     }
 
     /// Build a `Request`, which can be inspected, modified and executed with
@@ -103,10 +116,33 @@ impl RequestBuilder {
     ///
     /// This is similar to [`RequestBuilder::build()`], but also returns the
     /// embedded `VerityClient`.
-    pub fn build_split(self) -> (VerityClient, reqwest::Result<Request>) {
-        let Self { inner, client, .. } = self;
+    pub fn build_split(self) -> (VerityClient, Result<Request, VerityError>) {
+        let Self {
+            inner,
+            client,
+            redactions,
+        } = self;
         let (_, req) = inner.build_split();
 
+        let req = req.map_err(VerityError::from).and_then(|mut req| {
+            if !redactions.is_empty() {
+                // Keep the rules of a `T-REDACTED` header set by the caller
+                let mut rules = Vec::new();
+                for value in req.headers().get_all("T-REDACTED") {
+                    let value = value.to_str().map_err(|_| {
+                        VerityError::InvalidRedaction(format!("invalid rules {:?}", value))
+                    })?;
+                    rules.extend(Redaction::parse_header_value(value)?);
+                }
+                rules.extend(redactions);
+
+                let redacted = Redaction::to_header_value(&rules)?;
+                req.headers_mut()
+                    .insert("T-REDACTED", HeaderValue::from_str(&redacted)?);
+            }
+            Ok(req)
+        });
+
         (client, req)
     }
 
@@ -121,9 +157,9 @@ impl RequestBuilder {
     /// # Example
     ///
     /// ```no_run
-    /// # use anyhow::Error;
     /// # use verity_client::client::{VerityClient, VerityClientConfig};
-    /// # async fn run() -> Result<(), Error> {
+    /// # use verity_client::VerityError;
+    /// # async fn run() -> Result<(), VerityError> {
     ///
     ///
     /// let config = VerityClientConfig::new("http://127.0.0.1:8080");
@@ -135,8 +171,39 @@ impl RequestBuilder {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn send(self) -> anyhow::Result<VerityResponse> {
+    pub async fn send(self) -> Result<VerityResponse, VerityError> {
         let (mut client, req) = self.build_split();
         client.execute_request(req?).await
     }
+
+    /// Sends the request like [`RequestBuilder::send()`], but returns the response
+    /// without waiting for the proof. The proof is delivered by the returned
+    /// `ProofHandle`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the request is invalid or cannot be sent.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use verity_client::client::{VerityClient, VerityClientConfig};
+    /// # use verity_client::VerityError;
+    /// # async fn run() -> Result<(), VerityError> {
+    /// let config = VerityClientConfig::new("http://127.0.0.1:8080");
+    ///
+    /// let (response, proof) = VerityClient::new(config)
+    ///     .get("https://hyper.rs")
+    ///     .send_detached()
+    ///     .await?;
+    ///
+    /// let body = response.text().await?;
+    /// let proof = proof.await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_detached(self) -> Result<(Response, ProofHandle), VerityError> {
+        let (mut client, req) = self.build_split();
+        client.execute_detached(req?).await
+    }
 }
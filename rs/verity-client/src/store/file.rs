@@ -0,0 +1,92 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use verity_proof_bundle::ProofBundle;
+
+use super::{store_error, ProofStore};
+use crate::error::VerityError;
+
+/// A proof store keeping one JSON encoded bundle per file in a directory.
+///
+/// Files are named after the request id, e.g. `<dir>/<request id>.json`, so they can
+/// be inspected and copied with ordinary tools.
+#[derive(Debug, Clone)]
+pub struct FileProofStore {
+    dir: PathBuf,
+}
+
+impl FileProofStore {
+    /// Opens a store in `dir`, creating the directory if needed.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the directory cannot be created.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, VerityError> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir).map_err(|e| store_error(format!("{}: {}", dir.display(), e)))?;
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    /// The path a bundle is stored at.
+    ///
+    /// Request ids are restricted to characters that are safe in file names, so that
+    /// an id cannot point outside the store.
+    fn path(&self, request_id: &str) -> Result<PathBuf, VerityError> {
+        let is_safe = !request_id.is_empty()
+            && request_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !is_safe {
+            return Err(store_error(format!("invalid request id {:?}", request_id)));
+        }
+
+        Ok(self.dir.join(format!("{}.json", request_id)))
+    }
+}
+
+impl ProofStore for FileProofStore {
+    fn put(&self, bundle: &ProofBundle) -> Result<(), VerityError> {
+        let path = self.path(&bundle.request_id)?;
+        let json = bundle.to_json().map_err(store_error)?;
+
+        fs::write(&path, json).map_err(|e| store_error(format!("{}: {}", path.display(), e)))
+    }
+
+    fn get(&self, request_id: &str) -> Result<Option<ProofBundle>, VerityError> {
+        let path = self.path(request_id)?;
+        match fs::read_to_string(&path) {
+            Ok(json) => read_bundle(&path, &json).map(Some),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(store_error(format!("{}: {}", path.display(), err))),
+        }
+    }
+
+    fn list(&self) -> Result<Vec<ProofBundle>, VerityError> {
+        let entries = fs::read_dir(&self.dir)
+            .map_err(|e| store_error(format!("{}: {}", self.dir.display(), e)))?;
+
+        let mut bundles = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(store_error)?.path();
+            if path.extension().is_none_or(|extension| extension != "json") {
+                continue;
+            }
+
+            let json = fs::read_to_string(&path)
+                .map_err(|e| store_error(format!("{}: {}", path.display(), e)))?;
+            bundles.push(read_bundle(&path, &json)?);
+        }
+
+        bundles.sort_by(|a, b| (a.timestamp, &a.request_id).cmp(&(b.timestamp, &b.request_id)));
+
+        Ok(bundles)
+    }
+}
+
+fn read_bundle(path: &Path, json: &str) -> Result<ProofBundle, VerityError> {
+    ProofBundle::from_json(json).map_err(|e| store_error(format!("{}: {}", path.display(), e)))
+}
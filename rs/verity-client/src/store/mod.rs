@@ -0,0 +1,171 @@
+//! Persistence of proofs.
+//!
+//! A [`ProofStore`] archives [`ProofBundle`]s, for example for audits. Stores can be
+//! used directly, or set on the client with `VerityClientConfig::with_proof_store` to
+//! persist every proof as it arrives from the prover.
+//!
+//! [`FileProofStore`] keeps one JSON file per proof in a directory. With the `sqlite`
+//! feature, `SqliteProofStore` keeps proofs in a SQLite database.
+
+mod file;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use reqwest::Url;
+use verity_proof_bundle::ProofBundle;
+
+use crate::error::VerityError;
+
+pub use self::file::FileProofStore;
+#[cfg(feature = "sqlite")]
+pub use self::sqlite::SqliteProofStore;
+
+/// A place where proof bundles are archived.
+///
+/// Bundles are keyed by their request id. Implementations must be safe to share
+/// between the tasks awaiting proofs.
+pub trait ProofStore: Send + Sync {
+    /// Stores a bundle, replacing any bundle with the same request id.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the bundle cannot be written.
+    fn put(&self, bundle: &ProofBundle) -> Result<(), VerityError>;
+
+    /// Returns the bundle stored for a request id, if any.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the store cannot be read.
+    fn get(&self, request_id: &str) -> Result<Option<ProofBundle>, VerityError>;
+
+    /// Returns every stored bundle, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the store cannot be read.
+    fn list(&self) -> Result<Vec<ProofBundle>, VerityError>;
+
+    /// Returns the stored bundles matching `query`, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the store cannot be read.
+    fn query(&self, query: &ProofQuery) -> Result<Vec<ProofBundle>, VerityError> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .filter(|bundle| query.matches(bundle))
+            .collect())
+    }
+}
+
+/// Filters stored proofs by target host and by when they were received.
+///
+/// An empty query matches every proof. Bounds are inclusive and compared at the
+/// one second precision of `ProofBundle::timestamp`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProofQuery {
+    pub host: Option<String>,
+    pub since: Option<SystemTime>,
+    pub until: Option<SystemTime>,
+}
+
+impl ProofQuery {
+    /// Create a query matching every proof.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match proofs of requests to `host`, ignoring case.
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// Only match proofs received at or after `since`.
+    pub fn since(mut self, since: SystemTime) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Only match proofs received at or before `until`.
+    pub fn until(mut self, until: SystemTime) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Whether `bundle` matches this query.
+    pub fn matches(&self, bundle: &ProofBundle) -> bool {
+        let host_matches = self.host.as_deref().is_none_or(|host| {
+            bundle_host(bundle).is_some_and(|bundle_host| bundle_host.eq_ignore_ascii_case(host))
+        });
+        let started = self
+            .since
+            .is_none_or(|since| bundle.timestamp >= unix_timestamp(since));
+        let ended = self
+            .until
+            .is_some_and(|until| bundle.timestamp > unix_timestamp(until));
+
+        host_matches && started && !ended
+    }
+}
+
+/// The host of the target URL of a bundle.
+fn bundle_host(bundle: &ProofBundle) -> Option<String> {
+    Url::parse(&bundle.url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+}
+
+fn unix_timestamp(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+fn store_error(err: impl std::fmt::Display) -> VerityError {
+    VerityError::Store(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn bundle(url: &str, timestamp: u64) -> ProofBundle {
+        ProofBundle::new("proof", "key")
+            .with_request("id", "GET", url)
+            .with_timestamp(UNIX_EPOCH + Duration::from_secs(timestamp))
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert!(ProofQuery::new().matches(&bundle("https://example.com/a", 10)));
+        assert!(ProofQuery::new().matches(&bundle("not a url", 10)));
+    }
+
+    #[test]
+    fn query_matches_host_ignoring_case() {
+        let query = ProofQuery::new().host("API.example.com");
+
+        assert!(query.matches(&bundle("https://api.example.com/a?b=c", 10)));
+        assert!(!query.matches(&bundle("https://example.com/a", 10)));
+        assert!(!query.matches(&bundle("not a url", 10)));
+    }
+
+    #[test]
+    fn query_bounds_are_inclusive() {
+        let query = ProofQuery::new()
+            .since(UNIX_EPOCH + Duration::from_secs(10))
+            .until(UNIX_EPOCH + Duration::from_secs(20));
+
+        assert!(!query.matches(&bundle("https://example.com", 9)));
+        assert!(query.matches(&bundle("https://example.com", 10)));
+        assert!(query.matches(&bundle("https://example.com", 20)));
+        assert!(!query.matches(&bundle("https://example.com", 21)));
+    }
+}
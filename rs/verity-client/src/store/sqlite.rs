@@ -0,0 +1,133 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use verity_proof_bundle::ProofBundle;
+
+use super::{bundle_host, store_error, unix_timestamp, ProofQuery, ProofStore};
+use crate::error::VerityError;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS proofs (
+    request_id TEXT PRIMARY KEY NOT NULL,
+    host TEXT,
+    timestamp INTEGER NOT NULL,
+    bundle BLOB NOT NULL
+);
+CREATE INDEX IF NOT EXISTS proofs_host_timestamp ON proofs (host, timestamp);
+CREATE INDEX IF NOT EXISTS proofs_timestamp ON proofs (timestamp);
+";
+
+/// A proof store keeping bundles in a SQLite database.
+///
+/// Bundles are stored in their compact binary encoding, next to the target host and
+/// timestamp so that queries run in the database.
+pub struct SqliteProofStore {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteProofStore {
+    /// Opens the database at `path`, creating it if needed.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the database cannot be opened or initialized.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, VerityError> {
+        Self::new(Connection::open(path).map_err(store_error)?)
+    }
+
+    /// Opens a database that lives in memory until the store is dropped.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the database cannot be initialized.
+    pub fn open_in_memory() -> Result<Self, VerityError> {
+        Self::new(Connection::open_in_memory().map_err(store_error)?)
+    }
+
+    fn new(connection: Connection) -> Result<Self, VerityError> {
+        connection.execute_batch(SCHEMA).map_err(store_error)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn select(
+        &self,
+        host: Option<String>,
+        since: Option<u64>,
+        until: Option<u64>,
+    ) -> Result<Vec<ProofBundle>, VerityError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection
+            .prepare_cached(
+                "SELECT bundle FROM proofs
+                 WHERE (?1 IS NULL OR host = ?1)
+                   AND (?2 IS NULL OR timestamp >= ?2)
+                   AND (?3 IS NULL OR timestamp <= ?3)
+                 ORDER BY timestamp, request_id",
+            )
+            .map_err(store_error)?;
+
+        let rows = statement
+            .query_map(params![host, since, until], |row| row.get::<_, Vec<u8>>(0))
+            .map_err(store_error)?;
+
+        rows.map(|bytes| ProofBundle::from_bytes(&bytes.map_err(store_error)?).map_err(store_error))
+            .collect()
+    }
+}
+
+impl ProofStore for SqliteProofStore {
+    fn put(&self, bundle: &ProofBundle) -> Result<(), VerityError> {
+        let bytes = bundle.to_bytes().map_err(store_error)?;
+
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT OR REPLACE INTO proofs (request_id, host, timestamp, bundle)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    bundle.request_id,
+                    bundle_host(bundle),
+                    bundle.timestamp,
+                    bytes
+                ],
+            )
+            .map_err(store_error)?;
+
+        Ok(())
+    }
+
+    fn get(&self, request_id: &str) -> Result<Option<ProofBundle>, VerityError> {
+        let bytes: Option<Vec<u8>> = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT bundle FROM proofs WHERE request_id = ?1",
+                params![request_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(store_error)?;
+
+        bytes
+            .map(|bytes| ProofBundle::from_bytes(&bytes).map_err(store_error))
+            .transpose()
+    }
+
+    fn list(&self) -> Result<Vec<ProofBundle>, VerityError> {
+        self.select(None, None, None)
+    }
+
+    fn query(&self, query: &ProofQuery) -> Result<Vec<ProofBundle>, VerityError> {
+        self.select(
+            query.host.as_deref().map(str::to_ascii_lowercase),
+            query.since.map(unix_timestamp),
+            query.until.map(unix_timestamp),
+        )
+    }
+}
@@ -0,0 +1,79 @@
+//! Timing events and metrics for prover round trips.
+//!
+//! Every attempt to prove a request runs in a `verity_request` span carrying the
+//! `request_id`, the target `host` and the `prover` URL. Within that span, the client
+//! emits `tracing` events when the prover relays the response and when the proof
+//! arrives, times out or fails.
+//!
+//! With the `metrics` feature, the same measurements are recorded through the
+//! [`metrics`](https://docs.rs/metrics) facade under the names below, so any installed
+//! recorder, such as a Prometheus exporter, can collect them.
+
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use tracing::{info, warn};
+
+use crate::error::VerityError;
+
+/// Histogram of the time until the prover relays the response, in seconds.
+pub const RESPONSE_LATENCY_SECONDS: &str = "verity_response_latency_seconds";
+
+/// Histogram of the time until the proof arrives, in seconds.
+pub const PROOF_LATENCY_SECONDS: &str = "verity_proof_latency_seconds";
+
+/// Histogram of the size of received proofs, in bytes.
+pub const PROOF_SIZE_BYTES: &str = "verity_proof_size_bytes";
+
+/// Counter of proofs received.
+pub const PROOFS_SUCCEEDED_TOTAL: &str = "verity_proofs_succeeded_total";
+
+/// Counter of proofs that did not arrive within the proof timeout.
+pub const PROOFS_TIMED_OUT_TOTAL: &str = "verity_proofs_timed_out_total";
+
+/// Counter of proofs that could not be obtained for any other reason.
+pub const PROOFS_FAILED_TOTAL: &str = "verity_proofs_failed_total";
+
+/// Records that the prover relayed the response `latency` after the request was sent.
+pub(crate) fn response_received(latency: Duration, status: StatusCode) {
+    info!(
+        latency_ms = latency.as_millis() as u64,
+        status = status.as_u16(),
+        "prover relayed response"
+    );
+
+    #[cfg(feature = "metrics")]
+    metrics::histogram!(RESPONSE_LATENCY_SECONDS).record(latency.as_secs_f64());
+}
+
+/// Records that a proof of `size` bytes arrived `latency` after the request was sent.
+pub(crate) fn proof_received(latency: Duration, size: usize) {
+    info!(
+        latency_ms = latency.as_millis() as u64,
+        proof_size = size,
+        "proof received"
+    );
+
+    #[cfg(feature = "metrics")]
+    {
+        metrics::histogram!(PROOF_LATENCY_SECONDS).record(latency.as_secs_f64());
+        metrics::histogram!(PROOF_SIZE_BYTES).record(size as f64);
+        metrics::counter!(PROOFS_SUCCEEDED_TOTAL).increment(1);
+    }
+}
+
+/// Records that no proof arrived within the proof timeout.
+pub(crate) fn proof_timed_out(latency: Duration) {
+    warn!(latency_ms = latency.as_millis() as u64, "proof timed out");
+
+    #[cfg(feature = "metrics")]
+    metrics::counter!(PROOFS_TIMED_OUT_TOTAL).increment(1);
+}
+
+/// Records that the proof could not be obtained.
+pub(crate) fn proof_failed(latency: Duration, err: &VerityError) {
+    warn!(latency_ms = latency.as_millis() as u64, error = %err, "proof failed");
+
+    #[cfg(feature = "metrics")]
+    metrics::counter!(PROOFS_FAILED_TOTAL).increment(1);
+}
@@ -0,0 +1,269 @@
+//! Transports a proof is delivered over.
+//!
+//! The prover publishes the proof of a request on `/proof/{id}`. By default the client
+//! subscribes to it as a server-sent event stream. Some proxies buffer or cut event
+//! streams, so the proof can also be received over a WebSocket or by long-polling.
+//!
+//! Every transport delivers the same `pubkey|proof` message, and reconnects with an
+//! exponential backoff when the connection drops. Reconnects stop once the proof
+//! timeout elapses or the request or proof handle is cancelled.
+//!
+//! The encodings the client accepts are offered in the `T-PROOF-ENCODING` header on
+//! every transport. The prover may then send the proof compressed, in the text form
+//! described in `verity_proof_bundle::compression`.
+
+use std::time::Duration;
+
+use futures::StreamExt;
+use http::header::ACCEPT;
+use http::HeaderValue;
+use reqwest::{StatusCode, Url};
+use reqwest_eventsource::retry::Never;
+use reqwest_eventsource::{Error as EventSourceError, Event, EventSource};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::{Error as WebSocketError, Message};
+use tracing::{error, warn};
+use verity_proof_bundle::compression::ProofEncoding;
+
+use crate::auth::ProverAuth;
+use crate::client::authorize;
+use crate::error::VerityError;
+
+/// Header listing the encodings the prover may compress the proof with
+const PROOF_ENCODING_HEADER: &str = "T-PROOF-ENCODING";
+
+/// Delay before the first attempt to reconnect a dropped proof subscription
+const RECONNECT_DELAY: Duration = Duration::from_millis(250);
+
+/// Upper bound for the exponential backoff between proof subscription reconnects
+const MAX_RECONNECT_DELAY: Duration = Duration::from_millis(5000);
+
+/// How the client receives the proof of a request from the prover.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProofTransport {
+    /// Subscribe to `/proof/{id}` as a server-sent event stream, resuming from the last
+    /// received event via `Last-Event-ID` after a reconnect.
+    #[default]
+    Sse,
+    /// Open a WebSocket on `/proof/{id}` and wait for a text message carrying the proof.
+    ///
+    /// The socket is opened directly rather than through the configured `http_client`,
+    /// so its proxy and TLS settings do not apply. `VerityClient::new` rejects it in
+    /// combination with `ProverAuth::client_certificate`, which it could not present.
+    WebSocket,
+    /// Repeatedly `GET /proof/{id}` with `Accept: text/plain`. The prover answers
+    /// `200 OK` with the proof once it is ready, and `204 No Content` if it is not
+    /// ready yet, after which the client polls again.
+    LongPolling,
+}
+
+/// Receives the `pubkey|proof` message published on `url` over `transport`, offering
+/// the prover to compress the proof with any of `encodings`.
+pub(crate) async fn receive_proof_message(
+    transport: ProofTransport,
+    http_client: &reqwest::Client,
+    url: Url,
+    auth: Option<&ProverAuth>,
+    encodings: &[ProofEncoding],
+) -> Result<String, VerityError> {
+    let encodings = encoding_header(encodings)?;
+    let encodings = encodings.as_ref();
+
+    match transport {
+        ProofTransport::Sse => receive_event(http_client, url, auth, encodings).await,
+        ProofTransport::WebSocket => receive_socket_message(url, auth, encodings).await,
+        ProofTransport::LongPolling => poll_proof(http_client, url, auth, encodings).await,
+    }
+}
+
+/// The value of the `T-PROOF-ENCODING` header, or `None` if no encoding is accepted.
+fn encoding_header(encodings: &[ProofEncoding]) -> Result<Option<HeaderValue>, VerityError> {
+    if encodings.is_empty() {
+        return Ok(None);
+    }
+
+    let names: Vec<&str> = encodings.iter().map(|encoding| encoding.as_str()).collect();
+    Ok(Some(HeaderValue::from_str(&names.join(", "))?))
+}
+
+async fn receive_event(
+    http_client: &reqwest::Client,
+    url: Url,
+    auth: Option<&ProverAuth>,
+    encodings: Option<&HeaderValue>,
+) -> Result<String, VerityError> {
+    let mut last_event_id = String::new();
+    let mut reconnect_delay = RECONNECT_DELAY;
+
+    loop {
+        let mut request = authorize(http_client.get(url.clone()), auth)?;
+        if let Some(encodings) = encodings {
+            request = request.header(PROOF_ENCODING_HEADER, encodings.clone());
+        }
+        if !last_event_id.is_empty() {
+            request = request.header("Last-Event-ID", HeaderValue::from_str(&last_event_id)?);
+        }
+
+        let mut event_source =
+            EventSource::new(request).map_err(|e| VerityError::Prover(e.to_string()))?;
+        // Reconnects are driven here so they can be bounded by the proof timeout
+        event_source.set_retry_policy(Box::new(Never));
+
+        while let Some(event) = event_source.next().await {
+            match event {
+                Ok(Event::Open) => reconnect_delay = RECONNECT_DELAY,
+                // Keep-alive and other named events do not carry a proof
+                Ok(Event::Message(message)) if message.event != "message" => {}
+                Ok(Event::Message(message)) => return Ok(message.data),
+                Err(err) if is_transient_event_error(&err) => {
+                    warn!("proof stream interrupted, reconnecting: {}", err);
+                    break;
+                }
+                Err(err) => {
+                    error!("{}", err);
+                    return Err(err.into());
+                }
+            }
+        }
+
+        last_event_id = event_source.last_event_id().to_string();
+        event_source.close();
+
+        tokio::time::sleep(reconnect_delay).await;
+        reconnect_delay = (reconnect_delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+}
+
+async fn receive_socket_message(
+    url: Url,
+    auth: Option<&ProverAuth>,
+    encodings: Option<&HeaderValue>,
+) -> Result<String, VerityError> {
+    let mut url = url;
+    let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+    url.set_scheme(scheme)
+        .map_err(|()| VerityError::Prover(format!("invalid proof socket url {}", url)))?;
+
+    let mut reconnect_delay = RECONNECT_DELAY;
+
+    loop {
+        let mut request = url.as_str().into_client_request()?;
+        if let Some((name, value)) = auth.map(ProverAuth::header_pair).transpose()?.flatten() {
+            request.headers_mut().insert(name, value);
+        }
+        if let Some(encodings) = encodings {
+            request
+                .headers_mut()
+                .insert(PROOF_ENCODING_HEADER, encodings.clone());
+        }
+
+        match tokio_tungstenite::connect_async(request).await {
+            Ok((mut socket, _)) => {
+                reconnect_delay = RECONNECT_DELAY;
+
+                // Pings are answered while reading, other frames do not carry a proof
+                while let Some(message) = socket.next().await {
+                    match message {
+                        Ok(Message::Text(text)) => {
+                            // The socket has served its purpose, a failed close is harmless
+                            let _ = socket.close(None).await;
+                            return Ok(text);
+                        }
+                        Ok(_) => {}
+                        Err(err) => {
+                            warn!("proof socket interrupted, reconnecting: {}", err);
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(err) if is_transient_socket_error(&err) => {
+                warn!("proof socket failed to connect, reconnecting: {}", err);
+            }
+            Err(err) => {
+                error!("{}", err);
+                return Err(err.into());
+            }
+        }
+
+        tokio::time::sleep(reconnect_delay).await;
+        reconnect_delay = (reconnect_delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+}
+
+async fn poll_proof(
+    http_client: &reqwest::Client,
+    url: Url,
+    auth: Option<&ProverAuth>,
+    encodings: Option<&HeaderValue>,
+) -> Result<String, VerityError> {
+    let mut reconnect_delay = RECONNECT_DELAY;
+
+    loop {
+        let mut request = authorize(http_client.get(url.clone()), auth)?
+            .header(ACCEPT, HeaderValue::from_static("text/plain"));
+        if let Some(encodings) = encodings {
+            request = request.header(PROOF_ENCODING_HEADER, encodings.clone());
+        }
+
+        match request.send().await {
+            Ok(response) => match response.status() {
+                StatusCode::OK => match response.text().await {
+                    Ok(message) => return Ok(message),
+                    Err(err) => warn!("proof poll interrupted, polling again: {}", err),
+                },
+                // The prover held the poll until its own deadline, ask again right away
+                StatusCode::NO_CONTENT => {
+                    reconnect_delay = RECONNECT_DELAY;
+                    continue;
+                }
+                status if is_transient_status(status) => {
+                    warn!("proof poll answered {}, polling again", status);
+                }
+                status => {
+                    error!("proof poll rejected with status {}", status);
+                    return Err(VerityError::Prover(format!(
+                        "proof poll rejected with status {}",
+                        status
+                    )));
+                }
+            },
+            Err(err) => warn!("proof poll failed, polling again: {}", err),
+        }
+
+        tokio::time::sleep(reconnect_delay).await;
+        reconnect_delay = (reconnect_delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+}
+
+/// Whether a proof stream error is worth reconnecting for.
+///
+/// Dropped connections and gateway failures are transient, while a rejected request
+/// or a response that is not an event stream will not improve by retrying.
+fn is_transient_event_error(err: &EventSourceError) -> bool {
+    match err {
+        EventSourceError::Transport(_)
+        | EventSourceError::StreamEnded
+        | EventSourceError::Utf8(_)
+        | EventSourceError::Parser(_) => true,
+        EventSourceError::InvalidStatusCode(status, _) => is_transient_status(*status),
+        EventSourceError::InvalidContentType(_, _) | EventSourceError::InvalidLastEventId(_) => {
+            false
+        }
+    }
+}
+
+/// Whether a failure to open the proof socket is worth reconnecting for.
+fn is_transient_socket_error(err: &WebSocketError) -> bool {
+    match err {
+        WebSocketError::Http(response) => is_transient_status(response.status()),
+        WebSocketError::Url(_) | WebSocketError::HttpFormat(_) => false,
+        _ => true,
+    }
+}
+
+fn is_transient_status(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+}
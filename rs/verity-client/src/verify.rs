@@ -0,0 +1,452 @@
+use bytes::Bytes;
+use http::{HeaderMap, StatusCode};
+use reqwest::Url;
+use verity_proof_bundle::compression::decode_proof;
+use verity_verify_tls::{
+    HttpResponse, RedactedBytes, ServerNamePolicy, VerifiedHttpExchange, VerifiedTranscripts,
+};
+
+use crate::client::VerityResponse;
+use crate::error::VerityError;
+use crate::notary::{check_notary_key, TrustedNotaryKey};
+use crate::proof::ProvenRequest;
+use crate::redaction::{Redaction, RedactionTarget};
+
+/// Headers describing message framing, which a proxy may legitimately rewrite.
+const FRAMING_HEADERS: [&str; 4] = [
+    "connection",
+    "content-length",
+    "keep-alive",
+    "transfer-encoding",
+];
+
+/// A response whose proof was verified locally against a trusted notary public key.
+#[derive(Debug, Clone)]
+pub struct VerifiedResponse {
+    /// The status received by the client, which equals the notarized status.
+    pub status: StatusCode,
+    /// The headers received by the client. Every notarized header, apart from framing
+    /// headers, is one of them, but headers added on the way, for example by the
+    /// prover, were not notarized. Values the request asked to redact are only checked
+    /// for their length.
+    pub headers: HeaderMap,
+    /// The body received by the client. It has the length of the notarized body and
+    /// equals it on every byte, unless the request asked to redact part of the body.
+    pub body: Bytes,
+    /// The notarized request transcript, with redacted bytes replaced by `X` and
+    /// invalid UTF-8 replaced.
+    pub sent: String,
+    /// The notarized response transcript, with redacted bytes replaced by `X` and
+    /// invalid UTF-8 replaced.
+    pub recv: String,
+    /// The notarized request and response, parsed from the transcripts.
+    pub exchange: VerifiedHttpExchange,
+}
+
+impl VerityResponse {
+    /// Verifies the proof and checks it against the response received by the client.
+    ///
+    /// The notary public key the proof arrived with must be one of `trusted_keys`, since
+    /// a prover can sign proofs with any key it likes. The proof is then decompressed
+    /// if needed and verified with `verity_verify_tls::verify_proof_bytes`.
+    ///
+    /// The proof must be made with the host of the request URL and notarize exactly one
+    /// exchange, whose request has the method and target of the request sent. The
+    /// status, body and notarized headers of `subject` are compared with the notarized
+    /// response, so a prover cannot return a response that differs from the one it
+    /// proved. The transcript may only be redacted where the request asked for it, with
+    /// a response header or body rule, and redacted bytes are not compared.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with `VerityError::UntrustedNotary` if `trusted_keys` is empty
+    /// or trusts no key the proof was signed with, and fails if the response carries no
+    /// proof, if the proof is invalid, if it was made for another request, or if the
+    /// received response differs from the notarized one.
+    pub async fn verify(
+        self,
+        trusted_keys: &[TrustedNotaryKey],
+    ) -> Result<VerifiedResponse, VerityError> {
+        if self.proof.is_empty() {
+            return Err(VerityError::MissingProof);
+        }
+
+        // An empty list trusts every key when proving, but never when verifying
+        if trusted_keys.is_empty() {
+            return Err(VerityError::UntrustedNotary(self.notary_pub_key));
+        }
+        check_notary_key(trusted_keys, &self.notary_pub_key)?;
+
+        let proof =
+            decode_proof(&self.proof).map_err(|e| VerityError::Compression(e.to_string()))?;
+        let pub_key = self.notary_pub_key.replace("\\n", "\n");
+        let transcripts = verity_verify_tls::verify_proof_bytes(&proof, &pub_key)?;
+
+        let exchange = check_request(&self.request, &transcripts)?;
+
+        let status = self.subject.status();
+        let headers = self.subject.headers().clone();
+        let body = self.subject.bytes().await?;
+
+        check_response(
+            status,
+            &headers,
+            &body,
+            &exchange.response,
+            &self.request.redactions,
+        )?;
+
+        Ok(VerifiedResponse {
+            status,
+            headers,
+            body,
+            sent: transcripts.sent.text().into_owned(),
+            recv: transcripts.recv.text().into_owned(),
+            exchange,
+        })
+    }
+}
+
+/// Checks that the transcripts notarize exactly one exchange, made with the host of
+/// `request` and with its method and target, and returns it.
+fn check_request(
+    request: &ProvenRequest,
+    transcripts: &VerifiedTranscripts,
+) -> Result<VerifiedHttpExchange, VerityError> {
+    let mismatch = |reason: String| Err(VerityError::TranscriptMismatch(reason));
+
+    let host = request
+        .url
+        .host_str()
+        .ok_or_else(|| VerityError::TranscriptMismatch(format!("{} has no host", request.url)))?;
+    ServerNamePolicy::new()
+        .with_allowed_host(host)
+        .check(transcripts)?;
+
+    let mut exchanges = transcripts.http_exchanges()?;
+    if exchanges.len() != 1 {
+        return mismatch(format!(
+            "expected one notarized exchange, found {}",
+            exchanges.len()
+        ));
+    }
+    let exchange = exchanges.remove(0);
+
+    if exchange.request.method != request.method.as_str() {
+        return mismatch(format!(
+            "method {} differs from notarized method {}",
+            request.method, exchange.request.method
+        ));
+    }
+
+    // A range rule may hide the request target, which then cannot be compared
+    if !redacts_request_line(&request.redactions) {
+        let notarized = Url::parse(&exchange.request.url).ok();
+        let same_target = notarized.as_ref().is_some_and(|notarized| {
+            notarized.path() == request.url.path() && notarized.query() == request.url.query()
+        });
+        if !same_target {
+            return mismatch(format!(
+                "{} differs from notarized request {}",
+                request.url, exchange.request.url
+            ));
+        }
+    }
+
+    Ok(exchange)
+}
+
+/// Checks that a received response matches a notarized response, and that the notarized
+/// response is only redacted where `redactions` asked for it.
+fn check_response(
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &[u8],
+    notarized: &HttpResponse,
+    redactions: &[Redaction],
+) -> Result<(), VerityError> {
+    let mismatch = |reason: String| Err(VerityError::TranscriptMismatch(reason));
+
+    if notarized.status != status.as_u16() {
+        return mismatch(format!(
+            "status {} differs from notarized status {}",
+            status, notarized.status
+        ));
+    }
+
+    let notarized_headers: Vec<_> = notarized
+        .headers
+        .iter()
+        .map(|header| (header.name.to_ascii_lowercase(), &header.value))
+        .filter(|(name, _)| !FRAMING_HEADERS.contains(&name.as_str()))
+        .collect();
+
+    let unrequested = notarized_headers
+        .iter()
+        .any(|(name, value)| value.is_redacted() && !redacts_header(redactions, name))
+        || (notarized.body.is_redacted() && !redacts_body(redactions));
+    if unrequested {
+        return mismatch(
+            "transcript is redacted where the request asked for no redaction".to_string(),
+        );
+    }
+
+    // Every notarized header must have been received. Received headers need not
+    // have been notarized, since the prover adds its own
+    for (name, notarized_value) in &notarized_headers {
+        let received = headers
+            .get_all(name.as_str())
+            .iter()
+            .any(|value| matches_redacted(notarized_value, value.as_bytes()));
+        if !received {
+            return mismatch(format!("notarized header {} was not received", name));
+        }
+    }
+
+    if !matches_redacted(&notarized.body, body) {
+        return mismatch("body differs from notarized body".to_string());
+    }
+
+    Ok(())
+}
+
+/// Whether a response header rule of `redactions` hides the header `name`.
+fn redacts_header(redactions: &[Redaction], name: &str) -> bool {
+    redactions.iter().any(|redaction| {
+        matches!(
+            redaction,
+            Redaction::Header { target: RedactionTarget::Response, name: redacted }
+                if redacted.eq_ignore_ascii_case(name)
+        ) || matches!(
+            redaction,
+            Redaction::Range {
+                target: RedactionTarget::Response,
+                ..
+            }
+        )
+    })
+}
+
+/// Whether `redactions` hide part of the response body.
+fn redacts_body(redactions: &[Redaction]) -> bool {
+    redactions.iter().any(|redaction| {
+        matches!(
+            redaction,
+            Redaction::JsonPath {
+                target: RedactionTarget::Response,
+                ..
+            } | Redaction::Regex {
+                target: RedactionTarget::Response,
+                ..
+            } | Redaction::Range {
+                target: RedactionTarget::Response,
+                ..
+            }
+        )
+    })
+}
+
+/// Whether a request range rule of `redactions` may hide the request line.
+fn redacts_request_line(redactions: &[Redaction]) -> bool {
+    redactions.iter().any(|redaction| {
+        matches!(
+            redaction,
+            Redaction::Range {
+                target: RedactionTarget::Request,
+                ..
+            }
+        )
+    })
+}
+
+/// Compares a notarized value with a received one. Redacted bytes of the notarized value
+/// are not revealed, so only their number is compared.
+fn matches_redacted(notarized: &RedactedBytes, received: &[u8]) -> bool {
+    notarized.data.len() == received.len()
+        && notarized
+            .data
+            .iter()
+            .zip(&notarized.redacted)
+            .zip(received)
+            .all(|((n, redacted), r)| *redacted || n == r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    use http::{HeaderValue, Method};
+    use verity_verify_tls::parse_http_exchanges;
+
+    const TRANSCRIPT: &str = "HTTP/1.1 200 OK\r\n\
+        Content-Type: application/json\r\n\
+        Set-Cookie: session=XXXXXX\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        19\r\n\
+        {\"bitcoin\":{\"usd\":XXXXX}}\r\n\
+        0\r\n\
+        \r\n";
+
+    /// The transcript, with every `X` marked as redacted.
+    fn transcript(text: &str) -> RedactedBytes {
+        RedactedBytes {
+            data: text.as_bytes().to_vec(),
+            redacted: text.bytes().map(|b| b == b'X').collect(),
+        }
+    }
+
+    /// The response of the transcript, parsed the way `verify` parses it.
+    fn notarized(transcript: &RedactedBytes) -> HttpResponse {
+        let sent = self::transcript("GET / HTTP/1.1\r\nhost: example.com\r\n\r\n");
+        parse_http_exchanges(&sent, transcript)
+            .unwrap()
+            .remove(0)
+            .response
+    }
+
+    fn redactions() -> Vec<Redaction> {
+        vec![
+            Redaction::response_header("set-cookie"),
+            Redaction::response_json("bitcoin.usd"),
+        ]
+    }
+
+    fn received_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        headers.insert("set-cookie", HeaderValue::from_static("session=abc123"));
+        headers.insert("content-length", HeaderValue::from_static("25"));
+        headers.insert("t-proof-id", HeaderValue::from_static("1"));
+        headers
+    }
+
+    fn check(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Result<(), VerityError> {
+        check_response(
+            status,
+            headers,
+            body,
+            &notarized(&transcript(TRANSCRIPT)),
+            &redactions(),
+        )
+    }
+
+    #[test]
+    fn accepts_response_matching_transcript() {
+        let body = br#"{"bitcoin":{"usd":97334}}"#;
+        check(StatusCode::OK, &received_headers(), body).unwrap();
+    }
+
+    #[test]
+    fn rejects_altered_body() {
+        let body = br#"{"bitcoin":{"eur":97334}}"#;
+        let result = check(StatusCode::OK, &received_headers(), body);
+        assert!(matches!(result, Err(VerityError::TranscriptMismatch(_))));
+
+        // A literal `X` in the transcript is not a wildcard
+        let body = br#"{"bitcoin":{"usd":97334}}"#;
+        let altered = TRANSCRIPT.replace("\"bitcoin\"", "\"XXXXXXX\"");
+        let mut notarized_transcript = transcript(&altered);
+        notarized_transcript.redacted.fill(false);
+        let result = check_response(
+            StatusCode::OK,
+            &received_headers(),
+            body,
+            &notarized(&notarized_transcript),
+            &redactions(),
+        );
+        assert!(matches!(result, Err(VerityError::TranscriptMismatch(_))));
+    }
+
+    #[test]
+    fn rejects_altered_status_and_headers() {
+        let body = br#"{"bitcoin":{"usd":97334}}"#;
+        let result = check(StatusCode::CREATED, &received_headers(), body);
+        assert!(matches!(result, Err(VerityError::TranscriptMismatch(_))));
+
+        let mut headers = received_headers();
+        headers.insert("content-type", HeaderValue::from_static("text/html"));
+        let result = check(StatusCode::OK, &headers, body);
+        assert!(matches!(result, Err(VerityError::TranscriptMismatch(_))));
+
+        // Notarized headers must be received, but other headers may be added
+        let mut headers = received_headers();
+        headers.insert("x-forwarded-for", HeaderValue::from_static("127.0.0.1"));
+        check(StatusCode::OK, &headers, body).unwrap();
+
+        let mut headers = received_headers();
+        headers.remove("set-cookie");
+        let result = check(StatusCode::OK, &headers, body);
+        assert!(matches!(result, Err(VerityError::TranscriptMismatch(_))));
+    }
+
+    #[test]
+    fn rejects_redactions_the_request_did_not_ask_for() {
+        let body = br#"{"bitcoin":{"usd":97334}}"#;
+        let result = check_response(
+            StatusCode::OK,
+            &received_headers(),
+            body,
+            &notarized(&transcript(TRANSCRIPT)),
+            &[Redaction::response_header("set-cookie")],
+        );
+        assert!(matches!(result, Err(VerityError::TranscriptMismatch(_))));
+    }
+
+    fn proven_request(method: Method, url: &str) -> ProvenRequest {
+        ProvenRequest {
+            request_id: "1".to_string(),
+            method,
+            url: url.parse().unwrap(),
+            redactions: redactions(),
+            proven_at: SystemTime::now(),
+        }
+    }
+
+    fn transcripts(sent: &str, server_name: &str) -> VerifiedTranscripts {
+        VerifiedTranscripts {
+            sent: transcript(sent),
+            recv: transcript(TRANSCRIPT),
+            server_name: Some(server_name.to_string()),
+        }
+    }
+
+    const SENT: &str = "GET /simple/price?ids=bitcoin HTTP/1.1\r\nhost: example.com\r\n\r\n";
+
+    #[test]
+    fn accepts_proof_of_the_request_sent() {
+        let request = proven_request(Method::GET, "https://example.com/simple/price?ids=bitcoin");
+        let exchange = check_request(&request, &transcripts(SENT, "example.com")).unwrap();
+        assert_eq!(exchange.response.status, 200);
+    }
+
+    #[test]
+    fn rejects_proof_for_a_different_host() {
+        let request = proven_request(Method::GET, "https://example.com/simple/price?ids=bitcoin");
+        let result = check_request(&request, &transcripts(SENT, "attacker.com"));
+        assert!(matches!(result, Err(VerityError::Verification(_))));
+    }
+
+    #[test]
+    fn rejects_proof_for_a_different_request() {
+        let transcripts = transcripts(SENT, "example.com");
+
+        let request = proven_request(Method::POST, "https://example.com/simple/price?ids=bitcoin");
+        let result = check_request(&request, &transcripts);
+        assert!(matches!(result, Err(VerityError::TranscriptMismatch(_))));
+
+        let request = proven_request(Method::GET, "https://example.com/simple/price?ids=ether");
+        let result = check_request(&request, &transcripts);
+        assert!(matches!(result, Err(VerityError::TranscriptMismatch(_))));
+    }
+
+    #[test]
+    fn rejects_proofs_of_several_exchanges() {
+        let request = proven_request(Method::GET, "https://example.com/simple/price?ids=bitcoin");
+        let mut transcripts = transcripts(&SENT.repeat(2), "example.com");
+        transcripts.recv = transcript(&TRANSCRIPT.repeat(2));
+        let result = check_request(&request, &transcripts);
+        assert!(matches!(result, Err(VerityError::TranscriptMismatch(_))));
+    }
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use common::spawn_recording_server;
+use verity_client::client::{VerityClient, VerityClientConfig};
+use verity_client::{ProverAuth, VerityError};
+
+fn authenticated_client(base: String, auth: ProverAuth) -> VerityClient {
+    VerityClient::new(
+        VerityClientConfig::new(base)
+            .with_proof_timeout(Duration::from_millis(3000))
+            .with_prover_auth(auth),
+    )
+}
+
+#[tokio::test]
+async fn prover_auth_header_is_sent_to_all_prover_endpoints() {
+    let (base, seen) = spawn_recording_server().await;
+    let client = authenticated_client(base, ProverAuth::header("x-api-key", "secret").unwrap());
+
+    client.get("https://example.com/").send().await.unwrap();
+    client.get_notary_info().await.unwrap();
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 3);
+    for (path, headers) in seen.iter() {
+        // Only proxied requests need the prefix to keep the key from the target
+        let (sent, withheld) = if path == "proxy" {
+            ("t-x-api-key", "x-api-key")
+        } else {
+            ("x-api-key", "t-x-api-key")
+        };
+        assert_eq!(headers.get(sent).unwrap(), "secret", "{}", path);
+        assert!(!headers.contains_key(withheld), "{}", path);
+    }
+}
+
+#[tokio::test]
+async fn bearer_provider_is_called_for_every_prover_call() {
+    let (base, seen) = spawn_recording_server().await;
+    let issued = Arc::new(AtomicUsize::new(0));
+    let counter = issued.clone();
+    let client = authenticated_client(
+        base,
+        ProverAuth::bearer_provider(move || {
+            format!("token-{}", counter.fetch_add(1, Ordering::SeqCst))
+        }),
+    );
+
+    client.get("https://example.com/").send().await.unwrap();
+    client.get_notary_info().await.unwrap();
+
+    assert_eq!(issued.load(Ordering::SeqCst), 3);
+    let mut tokens: Vec<String> = seen
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(path, headers)| {
+            let name = if path == "proxy" {
+                "t-authorization"
+            } else {
+                "authorization"
+            };
+            headers[name].to_str().unwrap().to_string()
+        })
+        .collect();
+    tokens.sort();
+    assert_eq!(
+        tokens,
+        ["Bearer token-0", "Bearer token-1", "Bearer token-2"]
+    );
+}
+
+#[tokio::test]
+async fn prover_auth_leaves_request_headers_alone() {
+    let (base, seen) = spawn_recording_server().await;
+    let client = authenticated_client(base, ProverAuth::bearer("gateway-token"));
+
+    client
+        .get("https://example.com/")
+        .header("authorization", "Bearer target-token")
+        .send()
+        .await
+        .unwrap();
+
+    let seen = seen.lock().unwrap();
+    let (_, headers) = seen.iter().find(|(path, _)| path == "proxy").unwrap();
+    assert_eq!(headers["authorization"], "Bearer target-token");
+    assert_eq!(headers["t-authorization"], "Bearer gateway-token");
+}
+
+#[tokio::test]
+async fn prover_auth_conflicting_with_request_header_errors() {
+    let (base, seen) = spawn_recording_server().await;
+    let client = authenticated_client(base, ProverAuth::bearer("gateway-token"));
+
+    let result = client
+        .get("https://example.com/")
+        .header("t-authorization", "Bearer other-token")
+        .send()
+        .await;
+
+    assert!(matches!(result, Err(VerityError::Prover(_))));
+    assert!(seen.lock().unwrap().is_empty());
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::http::StatusCode;
+
+use common::spawn_mock_server;
+use verity_client::cassette::Cassette;
+use verity_client::client::{VerityClient, VerityClientConfig};
+use verity_client::notary::TrustedNotaryKey;
+use verity_client::VerityError;
+
+#[tokio::test]
+async fn cassette_records_and_replays_without_a_prover() {
+    let path = std::env::temp_dir().join(format!("verity-cassette-{}.json", uuid::Uuid::new_v4()));
+
+    let recorder = VerityClient::new(
+        VerityClientConfig::new(spawn_mock_server().await)
+            .with_proof_timeout(Duration::from_millis(3000))
+            .with_cassette(Cassette::record(&path)),
+    );
+
+    let recorded = recorder
+        .post("https://example.com/posts")
+        .json(&serde_json::json!({"title": "foo"}))
+        .send()
+        .await
+        .unwrap();
+    let recorded_proof = recorded.proof.clone();
+    assert_eq!(recorded.subject.text().await.unwrap(), "ok");
+
+    // Nothing listens on the prover URL during replay
+    let player = VerityClient::new(
+        VerityClientConfig::new("http://127.0.0.1:9")
+            .with_cassette(Cassette::replay(&path).unwrap()),
+    );
+
+    let replayed = player
+        .post("https://example.com/posts")
+        .json(&serde_json::json!({"title": "foo"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(replayed.subject.status(), StatusCode::OK);
+    assert_eq!(replayed.subject.headers()["T-PROOF-ID"], "1");
+    assert_eq!(replayed.proof, recorded_proof);
+    assert!(replayed.notary_pub_key.starts_with("-----BEGIN PUBLIC KEY"));
+    assert_eq!(replayed.subject.text().await.unwrap(), "ok");
+
+    let unrecorded = player
+        .post("https://example.com/posts")
+        .json(&serde_json::json!({"title": "bar"}))
+        .send()
+        .await;
+    assert!(matches!(unrecorded, Err(VerityError::Cassette(_))));
+
+    // Recorded proofs are held to the trusted notary keys like live ones
+    let pinned = VerityClient::new(
+        VerityClientConfig::new("http://127.0.0.1:9")
+            .with_trusted_notary_key(TrustedNotaryKey::new("other-key"))
+            .with_cassette(Cassette::replay(&path).unwrap()),
+    );
+    let untrusted = pinned
+        .post("https://example.com/posts")
+        .json(&serde_json::json!({"title": "foo"}))
+        .send()
+        .await;
+    assert!(matches!(untrusted, Err(VerityError::UntrustedNotary(_))));
+
+    std::fs::remove_file(path).unwrap();
+}
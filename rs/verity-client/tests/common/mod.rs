@@ -0,0 +1,167 @@
+//! A minimal in-process prover shared by the integration tests.
+#![allow(dead_code)]
+
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::response::sse::{Event as SseEvent, Sse};
+use axum::routing::MethodRouter;
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use futures_util::stream;
+use tokio::net::TcpListener;
+
+use verity_client::client::{NotaryInformation, VerityClient, VerityClientConfig};
+use verity_client::ProofTransport;
+
+/// The notary key every fixture prover signs its proof messages with.
+pub const NOTARY_KEY: &str = "-----BEGIN PUBLIC KEY---...";
+
+/// Requests seen by a recording prover, keyed by endpoint.
+pub type Seen = Arc<Mutex<Vec<(String, HeaderMap)>>>;
+
+/// Serves `app` on a random local port and returns its base URL.
+pub async fn serve(app: Router) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service())
+            .await
+            .unwrap()
+    });
+
+    format!("http://{}", addr)
+}
+
+/// Returns a base URL nobody listens on.
+pub async fn unreachable_url() -> String {
+    // Bind and immediately drop a listener to obtain a free port
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    format!("http://{}", addr)
+}
+
+pub fn notary_information(version: &str) -> NotaryInformation {
+    NotaryInformation {
+        version: version.to_string(),
+        public_key: NOTARY_KEY.to_string(),
+        git_commit_hash: "0000000000000000000000000000000000000000".to_string(),
+        git_commit_timestamp: "0".to_string(),
+    }
+}
+
+/// Spawns a prover that promises a proof for every request and delivers it over SSE.
+pub async fn spawn_mock_server() -> String {
+    let app = Router::new()
+        .route(
+            "/notaryinfo",
+            get(|| async { Json(notary_information("test")) }),
+        )
+        .route("/proxy", post(proxy_handler).get(proxy_handler))
+        .route("/proof/:id", get(proof_handler));
+
+    serve(app).await
+}
+
+pub async fn proxy_handler() -> impl IntoResponse {
+    // Return a response header to indicate proof will arrive on SSE
+    let mut headers = HeaderMap::new();
+    headers.insert("T-PROOF-ID", HeaderValue::from_static("1"));
+    (StatusCode::OK, headers, "ok")
+}
+
+pub async fn proof_handler(Path(id): Path<String>) -> impl IntoResponse {
+    // Simulate SSE stream. For simplicity, send a single event and close.
+    // Format: notary_pub_key|proof
+    let body = format!("data: {}|proof-for-{}\n\n", NOTARY_KEY, id);
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "content-type",
+        HeaderValue::from_static("text/event-stream"),
+    );
+    (headers, body)
+}
+
+/// An SSE proof stream that stays open without ever sending a proof.
+pub async fn proof_never() -> impl IntoResponse {
+    let pending = stream::pending::<Result<SseEvent, Infallible>>();
+    Sse::new(pending)
+}
+
+pub async fn spawn_recording_server() -> (String, Seen) {
+    spawn_recording_server_with_version("test").await
+}
+
+/// Spawns a prover like `spawn_mock_server` that records the headers of every call and
+/// reports `version` from `/notaryinfo`.
+pub async fn spawn_recording_server_with_version(version: &'static str) -> (String, Seen) {
+    async fn proxy(State(seen): State<Seen>, headers: HeaderMap) -> impl IntoResponse {
+        seen.lock().unwrap().push(("proxy".to_string(), headers));
+        proxy_handler().await
+    }
+
+    async fn proof(
+        State(seen): State<Seen>,
+        id: Path<String>,
+        headers: HeaderMap,
+    ) -> impl IntoResponse {
+        seen.lock().unwrap().push(("proof".to_string(), headers));
+        proof_handler(id).await
+    }
+
+    let notary_info = move |State(seen): State<Seen>, headers: HeaderMap| async move {
+        seen.lock()
+            .unwrap()
+            .push(("notaryinfo".to_string(), headers));
+        Json(notary_information(version))
+    };
+
+    let seen = Seen::default();
+    let app = Router::new()
+        .route("/proxy", get(proxy).post(proxy))
+        .route("/proof/:id", get(proof))
+        .route("/notaryinfo", get(notary_info))
+        .with_state(seen.clone());
+
+    (serve(app).await, seen)
+}
+
+pub fn seen_paths(seen: &Mutex<Vec<(String, HeaderMap)>>) -> Vec<String> {
+    seen.lock()
+        .unwrap()
+        .iter()
+        .map(|(path, _)| path.clone())
+        .collect()
+}
+
+/// Serves `proof` on `/proof/:id` next to a proxy that always promises a proof.
+pub async fn spawn_proof_server(proof: MethodRouter) -> String {
+    let app = Router::new()
+        .route("/proxy", get(proxy_handler).post(proxy_handler))
+        .route("/proof/:id", proof);
+
+    serve(app).await
+}
+
+pub fn client(base: impl Into<String>) -> VerityClient {
+    VerityClient::new(
+        VerityClientConfig::new(base.into()).with_proof_timeout(Duration::from_millis(3000)),
+    )
+}
+
+pub fn transport_client(base: String, transport: ProofTransport) -> VerityClient {
+    VerityClient::new(
+        VerityClientConfig::new(base)
+            .with_proof_timeout(Duration::from_millis(3000))
+            .with_proof_transport(transport),
+    )
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::ws::{Message as WsMessage, WebSocketUpgrade};
+use axum::response::sse::{Event as SseEvent, Sse};
+use axum::{extract::State, http::HeaderMap, response::IntoResponse, routing::get};
+use futures_util::stream;
+
+use common::{spawn_proof_server, transport_client, NOTARY_KEY};
+use verity_client::client::{VerityClient, VerityClientConfig};
+use verity_client::store::FileProofStore;
+use verity_client::{ProofEncoding, ProofStore, ProofTransport, VerityError};
+
+/// A proof large and repetitive enough to compress well.
+fn large_proof() -> String {
+    format!(r#"{{"substrings":[{}]}}"#, vec!["0"; 4096].join(","))
+}
+
+/// Serves `large_proof` over every transport, compressed with the first encoding the
+/// client offers in `T-PROOF-ENCODING`, and records the offered encodings and the size
+/// of each sent message.
+async fn spawn_compressing_server() -> (String, Arc<Mutex<Vec<(Option<String>, usize)>>>) {
+    type Sent = Arc<Mutex<Vec<(Option<String>, usize)>>>;
+
+    async fn proof(
+        State(sent): State<Sent>,
+        ws: Option<WebSocketUpgrade>,
+        headers: HeaderMap,
+    ) -> axum::response::Response {
+        let offered = headers
+            .get("t-proof-encoding")
+            .map(|value| value.to_str().unwrap().to_string());
+        let proof = match offered
+            .as_deref()
+            .and_then(|offered| offered.split(',').next())
+        {
+            Some(encoding) => encoding
+                .parse::<ProofEncoding>()
+                .unwrap()
+                .encode(&large_proof())
+                .unwrap(),
+            None => large_proof(),
+        };
+        let message = format!("{}|{}", NOTARY_KEY, proof);
+        sent.lock().unwrap().push((offered, message.len()));
+
+        match ws {
+            Some(ws) => ws
+                .on_upgrade(|mut socket| async move {
+                    socket.send(WsMessage::Text(message)).await.unwrap();
+                    while socket.recv().await.is_some() {}
+                })
+                .into_response(),
+            None if headers["accept"] == "text/plain" => message.into_response(),
+            None => Sse::new(stream::once(async move {
+                Ok::<_, Infallible>(SseEvent::default().data(message))
+            }))
+            .into_response(),
+        }
+    }
+
+    let sent = Sent::default();
+    let base = spawn_proof_server(get(proof).with_state(sent.clone())).await;
+    (base, sent)
+}
+
+#[tokio::test]
+async fn compressed_proofs_are_negotiated_on_every_transport() {
+    let (base, sent) = spawn_compressing_server().await;
+
+    for transport in [
+        ProofTransport::Sse,
+        ProofTransport::WebSocket,
+        ProofTransport::LongPolling,
+    ] {
+        let client = VerityClient::new(
+            VerityClientConfig::new(base.clone())
+                .with_proof_timeout(Duration::from_millis(3000))
+                .with_proof_transport(transport)
+                .with_accepted_proof_encoding(ProofEncoding::Zstd)
+                .with_accepted_proof_encoding(ProofEncoding::Gzip),
+        );
+
+        let res = client.get("https://example.com/").send().await.unwrap();
+        assert_eq!(res.proof, large_proof(), "{:?}", transport);
+    }
+
+    let sent = sent.lock().unwrap();
+    assert_eq!(sent.len(), 3);
+    for (offered, size) in sent.iter() {
+        assert_eq!(offered.as_deref(), Some("zstd, gzip"));
+        assert!(*size < large_proof().len() / 4);
+    }
+}
+
+#[tokio::test]
+async fn proofs_are_uncompressed_unless_offered() {
+    let (base, sent) = spawn_compressing_server().await;
+    let client = transport_client(base, ProofTransport::Sse);
+
+    let res = client.get("https://example.com/").send().await.unwrap();
+    assert_eq!(res.proof, large_proof());
+    assert_eq!(sent.lock().unwrap()[0].0, None);
+}
+
+#[tokio::test]
+async fn proofs_are_kept_compressed_when_asked() {
+    let dir = std::env::temp_dir().join(format!("verity-proofs-{}", uuid::Uuid::new_v4()));
+    let store = FileProofStore::open(&dir).unwrap();
+
+    let (base, sent) = spawn_compressing_server().await;
+    let client = VerityClient::new(
+        VerityClientConfig::new(base)
+            .with_proof_timeout(Duration::from_millis(3000))
+            .with_accepted_proof_encoding(ProofEncoding::Zstd)
+            .with_compressed_proofs(ProofEncoding::Gzip)
+            .with_proof_store(store.clone()),
+    );
+
+    // The prover sends zstd, which is recompressed with the kept encoding
+    let res = client.get("https://example.com/").send().await.unwrap();
+    assert_eq!(sent.lock().unwrap()[0].0.as_deref(), Some("zstd, gzip"));
+    assert!(res.proof.starts_with("gzip:"));
+
+    let stored = store.get(&res.request.request_id).unwrap().unwrap();
+    assert_eq!(stored.proof, res.proof);
+    assert_eq!(stored.proof_json().unwrap(), large_proof());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn corrupt_compressed_proofs_error() {
+    async fn proof_corrupt() -> impl IntoResponse {
+        format!("{}|zstd:AAAA", NOTARY_KEY)
+    }
+
+    let base = spawn_proof_server(get(proof_corrupt)).await;
+    let client = transport_client(base, ProofTransport::LongPolling);
+
+    let res = client.get("https://example.com/").send().await;
+    assert!(matches!(res, Err(VerityError::Compression(_))));
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{spawn_recording_server, unreachable_url};
+use verity_client::client::{VerityClient, VerityClientConfig};
+use verity_client::ProverSelection;
+
+#[tokio::test]
+async fn unreachable_prover_fails_over_to_the_next_one() {
+    let (base, seen) = spawn_recording_server().await;
+    let client = VerityClient::new(
+        VerityClientConfig::new(unreachable_url().await)
+            .with_additional_prover(base)
+            .with_proof_timeout(Duration::from_millis(3000)),
+    );
+
+    let res = client.get("https://example.com/").send().await.unwrap();
+    assert!(res.proof.starts_with("proof-for-"));
+    assert!(client.get_notary_info().await.is_ok());
+
+    let status = client.check_provers().await;
+    assert!(!status[0].healthy);
+    assert!(status[1].healthy && status[1].latency.is_some());
+
+    let paths: Vec<String> = seen
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(p, _)| p.clone())
+        .collect();
+    assert!(paths.contains(&"proxy".to_string()));
+    assert!(paths.contains(&"proof".to_string()));
+}
+
+#[tokio::test]
+async fn round_robin_keeps_proof_subscription_on_the_same_prover() {
+    let (first, first_seen) = spawn_recording_server().await;
+    let (second, second_seen) = spawn_recording_server().await;
+    let client = VerityClient::new(
+        VerityClientConfig::new(first)
+            .with_additional_prover(second)
+            .with_prover_selection(ProverSelection::RoundRobin)
+            .with_proof_timeout(Duration::from_millis(3000)),
+    );
+
+    for _ in 0..4 {
+        client.get("https://example.com/").send().await.unwrap();
+    }
+
+    for seen in [first_seen, second_seen] {
+        let seen = seen.lock().unwrap();
+        let count = |path: &str| seen.iter().filter(|(p, _)| p == path).count();
+        assert_eq!(count("proxy"), 2);
+        assert_eq!(count("proof"), 2);
+    }
+}
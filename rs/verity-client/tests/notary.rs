@@ -0,0 +1,117 @@
+mod common;
+
+use std::time::{Duration, SystemTime};
+
+use common::{
+    seen_paths, spawn_mock_server, spawn_recording_server, spawn_recording_server_with_version,
+};
+use verity_client::client::{VerityClient, VerityClientConfig};
+use verity_client::notary::TrustedNotaryKey;
+use verity_client::{VerityError, VersionReq};
+
+fn pinned_client(base: String, key: TrustedNotaryKey) -> VerityClient {
+    VerityClient::new(
+        VerityClientConfig::new(base)
+            .with_proof_timeout(Duration::from_millis(3000))
+            .with_trusted_notary_key(key),
+    )
+}
+
+#[tokio::test]
+async fn pinned_notary_key_is_accepted() {
+    let client = pinned_client(
+        spawn_mock_server().await,
+        TrustedNotaryKey::new("-----BEGIN PUBLIC KEY---..."),
+    );
+
+    let res = client
+        .get("https://jsonplaceholder.typicode.com/posts")
+        .send()
+        .await
+        .unwrap();
+    assert!(res.proof.starts_with("proof-for-"));
+    assert!(client.get_notary_info().await.is_ok());
+}
+
+#[tokio::test]
+async fn untrusted_notary_key_is_rejected() {
+    let client = pinned_client(
+        spawn_mock_server().await,
+        TrustedNotaryKey::new("-----BEGIN PUBLIC KEY---other"),
+    );
+
+    let result = client
+        .get("https://jsonplaceholder.typicode.com/posts")
+        .send()
+        .await;
+    assert!(matches!(result, Err(VerityError::UntrustedNotary(_))));
+
+    let result = client.get_notary_info().await;
+    assert!(matches!(result, Err(VerityError::UntrustedNotary(_))));
+}
+
+#[tokio::test]
+async fn expired_notary_key_is_rejected() {
+    let expired = TrustedNotaryKey::new("-----BEGIN PUBLIC KEY---...")
+        .valid_until(SystemTime::now() - Duration::from_secs(60));
+    let client = pinned_client(spawn_mock_server().await, expired);
+
+    let result = client
+        .get("https://jsonplaceholder.typicode.com/posts")
+        .send()
+        .await;
+    assert!(matches!(result, Err(VerityError::UntrustedNotary(_))));
+}
+
+#[tokio::test]
+async fn notary_info_is_cached_for_its_ttl() {
+    let (base, seen) = spawn_recording_server().await;
+
+    let cached = VerityClient::new(VerityClientConfig::new(base.clone()));
+    cached.get_notary_info().await.unwrap();
+    cached.get_notary_info().await.unwrap();
+    assert_eq!(seen_paths(&seen), ["notaryinfo"]);
+
+    let uncached =
+        VerityClient::new(VerityClientConfig::new(base).with_notary_info_ttl(Duration::ZERO));
+    uncached.get_notary_info().await.unwrap();
+    uncached.get_notary_info().await.unwrap();
+    assert_eq!(seen_paths(&seen), ["notaryinfo"; 3]);
+}
+
+#[tokio::test]
+async fn incompatible_notary_fails_before_proxying() {
+    let (base, seen) = spawn_recording_server().await;
+    let client = VerityClient::new(VerityClientConfig::new(base).with_notary_compatibility_check());
+
+    let result = client.get("https://example.com/").send().await;
+    assert!(matches!(
+        result,
+        Err(VerityError::IncompatibleNotary { ref version, .. }) if version == "test"
+    ));
+    assert!(matches!(
+        client.get_notary_info().await,
+        Err(VerityError::IncompatibleNotary { .. })
+    ));
+
+    // The request never reached the prover, and the notary information was reused
+    assert_eq!(seen_paths(&seen), ["notaryinfo"]);
+}
+
+#[tokio::test]
+async fn compatible_notary_is_checked_once_per_ttl() {
+    let (base, seen) = spawn_recording_server_with_version("0.1.0-alpha.6").await;
+    let client = VerityClient::new(
+        VerityClientConfig::new(base)
+            .with_proof_timeout(Duration::from_millis(3000))
+            .with_notary_version_req(VersionReq::parse("=0.1.0-alpha.6").unwrap()),
+    );
+
+    client.get("https://example.com/").send().await.unwrap();
+    client.get("https://example.com/").send().await.unwrap();
+
+    let paths = seen_paths(&seen);
+    assert_eq!(paths[0], "notaryinfo");
+    assert_eq!(paths.iter().filter(|path| *path == "notaryinfo").count(), 1);
+    assert_eq!(paths.iter().filter(|path| *path == "proxy").count(), 2);
+}
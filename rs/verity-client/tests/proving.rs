@@ -0,0 +1,543 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+
+use common::{
+    client, notary_information, proof_handler, proof_never, proxy_handler, serve,
+    spawn_mock_server, unreachable_url, NOTARY_KEY,
+};
+use verity_client::client::{VerityClient, VerityClientConfig};
+use verity_client::{ProofBundle, Redaction, VerityError};
+
+#[tokio::test]
+async fn get_notary_info_works() {
+    let client = client(spawn_mock_server().await);
+
+    let info = client.get_notary_info().await.unwrap();
+    assert_eq!(info.git_commit_hash.len(), 40);
+    assert!(info.public_key.starts_with("-----BEGIN PUBLIC KEY"));
+}
+
+#[tokio::test]
+async fn get_and_post_request_with_proof() {
+    let client = client(spawn_mock_server().await);
+
+    // GET
+    let res = client
+        .get("https://jsonplaceholder.typicode.com/posts")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.subject.status(), StatusCode::OK);
+    assert!(res.notary_pub_key.starts_with("-----BEGIN PUBLIC KEY"));
+    assert!(res.proof.starts_with("proof-for-"));
+
+    // POST
+    let res = client
+        .post("https://jsonplaceholder.typicode.com/posts")
+        .json(&serde_json::json!({"title":"foo","body":"bar","userId":1}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.subject.status(), StatusCode::OK);
+    assert!(res.notary_pub_key.starts_with("-----BEGIN PUBLIC KEY"));
+    assert!(res.proof.starts_with("proof-for-"));
+}
+
+#[tokio::test]
+async fn response_packs_into_proof_bundle() {
+    let client = client(spawn_mock_server().await);
+
+    let res = client
+        .post("https://jsonplaceholder.typicode.com/posts")
+        .redact(Redaction::response_json("id"))
+        .send()
+        .await
+        .unwrap();
+    let bundle = res.to_bundle();
+
+    assert_eq!(bundle.proof, format!("proof-for-{}", bundle.request_id));
+    assert_eq!(bundle.notary_pub_key, res.notary_pub_key);
+    assert_eq!(bundle.method, "POST");
+    assert_eq!(bundle.url, "https://jsonplaceholder.typicode.com/posts");
+    assert_eq!(bundle.redactions, ["res:body:id"]);
+    assert!(bundle.notary_info.is_none());
+
+    let info = client.get_notary_info().await.unwrap();
+    let bundle = bundle.with_notary_info(info);
+    let decoded = ProofBundle::decode(&bundle.to_bytes().unwrap()).unwrap();
+    assert_eq!(decoded, bundle);
+}
+
+#[tokio::test]
+async fn no_proof_header_returns_immediately() {
+    // Custom server where /proxy does NOT include T-PROOF-ID
+    async fn proxy_no_proof() -> impl IntoResponse {
+        (StatusCode::OK, "ok")
+    }
+
+    let app = Router::new()
+        .route("/proxy", get(proxy_no_proof).post(proxy_no_proof))
+        .route("/proof/:id", get(proof_never))
+        .route(
+            "/notaryinfo",
+            get(|| async { Json(notary_information("test")) }),
+        );
+
+    let client = VerityClient::new(
+        VerityClientConfig::new(serve(app).await).with_proof_timeout(Duration::from_millis(500)),
+    );
+
+    let res = client.get("https://example.com/").send().await.unwrap();
+
+    assert_eq!(res.subject.status(), StatusCode::OK);
+    assert_eq!(res.notary_pub_key, "");
+    assert_eq!(res.proof, "");
+}
+
+#[tokio::test]
+async fn proof_timeout_errors() {
+    // Server that sets T-PROOF-ID but never emits SSE
+    async fn proxy_with_proof_header(mut headers: HeaderMap) -> impl IntoResponse {
+        headers.insert("T-PROOF-ID", HeaderValue::from_static("1"));
+        (StatusCode::OK, headers, "ok")
+    }
+
+    let app = Router::new()
+        .route(
+            "/proxy",
+            get(proxy_with_proof_header).post(proxy_with_proof_header),
+        )
+        .route(
+            "/notaryinfo",
+            get(|| async { Json(notary_information("test")) }),
+        )
+        .route("/proof/:id", get(proof_never));
+
+    let client = VerityClient::new(
+        VerityClientConfig::new(serve(app).await).with_proof_timeout(Duration::from_millis(200)),
+    );
+
+    let res = client.get("https://example.com/").send().await;
+    assert!(matches!(res, Err(VerityError::Timeout)));
+}
+
+#[tokio::test]
+async fn malformed_proof_message_errors() {
+    // Server that emits a proof message without the `pubkey|proof` separator
+    async fn proof_malformed() -> impl IntoResponse {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "content-type",
+            HeaderValue::from_static("text/event-stream"),
+        );
+        (headers, "data: not-a-proof\n\n")
+    }
+
+    let app = Router::new()
+        .route("/proxy", get(proxy_handler).post(proxy_handler))
+        .route("/proof/:id", get(proof_malformed));
+    let client = client(serve(app).await);
+
+    let res = client.get("https://example.com/").send().await;
+    assert!(matches!(res, Err(VerityError::InvalidProofMessage)));
+}
+
+#[tokio::test]
+async fn unreachable_prover_is_a_transport_error() {
+    let client = VerityClient::new(
+        VerityClientConfig::new(unreachable_url().await)
+            .with_proof_timeout(Duration::from_millis(500)),
+    );
+
+    let res = client.get("https://example.com/").send().await;
+    assert!(matches!(res, Err(VerityError::Transport(_))));
+}
+
+#[tokio::test]
+async fn proof_stream_reconnects_with_last_event_id() {
+    // Server whose first proof stream drops after a keep-alive, and which only
+    // delivers the proof to a reconnect that resumes from the last event.
+    #[derive(Clone, Default)]
+    struct ProofState {
+        connections: Arc<AtomicUsize>,
+        last_event_ids: Arc<Mutex<Vec<Option<String>>>>,
+    }
+
+    async fn proof_flaky(
+        State(state): State<ProofState>,
+        Path(id): Path<String>,
+        headers: HeaderMap,
+    ) -> impl IntoResponse {
+        let attempt = state.connections.fetch_add(1, Ordering::SeqCst);
+        let last_event_id = headers
+            .get("last-event-id")
+            .map(|v| v.to_str().unwrap().to_string());
+        state.last_event_ids.lock().unwrap().push(last_event_id);
+
+        let body = if attempt == 0 {
+            // Close the connection right after a keep-alive event
+            "event: ping\nid: 42\ndata: keep-alive\n\n".to_string()
+        } else {
+            format!("data: {}|proof-for-{}\n\n", NOTARY_KEY, id)
+        };
+
+        let mut h = HeaderMap::new();
+        h.insert(
+            "content-type",
+            HeaderValue::from_static("text/event-stream"),
+        );
+        (h, body)
+    }
+
+    let state = ProofState::default();
+    let app = Router::new()
+        .route("/proxy", get(proxy_handler).post(proxy_handler))
+        .route("/proof/:id", get(proof_flaky))
+        .with_state(state.clone());
+    let client = client(serve(app).await);
+
+    let res = client.get("https://example.com/").send().await.unwrap();
+    assert!(res.proof.starts_with("proof-for-"));
+
+    assert_eq!(state.connections.load(Ordering::SeqCst), 2);
+    assert_eq!(
+        *state.last_event_ids.lock().unwrap(),
+        vec![None, Some("42".to_string())]
+    );
+}
+
+#[tokio::test]
+async fn redactions_are_sent_to_prover() {
+    // Server that echoes the T-REDACTED header it received on /proxy
+    async fn proxy_echo_redacted(headers: HeaderMap) -> impl IntoResponse {
+        let redacted = headers
+            .get("T-REDACTED")
+            .map(|v| v.to_str().unwrap().to_string())
+            .unwrap_or_default();
+        (StatusCode::OK, redacted)
+    }
+
+    let app = Router::new()
+        .route("/proxy", get(proxy_echo_redacted).post(proxy_echo_redacted))
+        .route("/proof/:id", get(proof_handler));
+    let client = client(serve(app).await);
+
+    let res = client
+        .post("https://example.com/")
+        .redact(Redaction::request_header("authorization"))
+        .redact(Redaction::response_json("data.token"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        res.subject.text().await.unwrap(),
+        "req:header:authorization, res:body:data.token"
+    );
+
+    // Rules set on the raw header are kept alongside typed ones
+    let res = client
+        .post("https://example.com/")
+        .header("T-REDACTED", "req:body:password")
+        .redact(Redaction::response_json("data.token"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        res.subject.text().await.unwrap(),
+        "req:body:password, res:body:data.token"
+    );
+
+    // An invalid rule surfaces as an error instead of a panic
+    let res = client
+        .get("https://example.com/")
+        .redact(Redaction::request_header("not a header"))
+        .send()
+        .await;
+    assert!(matches!(res, Err(VerityError::InvalidRedaction(_))));
+
+    let res = client
+        .get("https://example.com/")
+        .header("T-REDACTED", "res:regex:(")
+        .redact(Redaction::response_json("data.token"))
+        .send()
+        .await;
+    assert!(matches!(res, Err(VerityError::InvalidRedaction(_))));
+}
+
+#[tokio::test]
+async fn detached_response_arrives_before_proof() {
+    async fn proof_delayed(Path(id): Path<String>) -> impl IntoResponse {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "content-type",
+            HeaderValue::from_static("text/event-stream"),
+        );
+        (headers, format!("data: pubkey|proof-for-{}\n\n", id))
+    }
+
+    let app = Router::new()
+        .route("/proxy", get(proxy_handler).post(proxy_handler))
+        .route("/proof/:id", get(proof_delayed));
+    let client = client(serve(app).await);
+
+    let (response, proof) = client
+        .get("https://example.com/")
+        .send_detached()
+        .await
+        .unwrap();
+    assert!(!proof.is_finished());
+    assert_eq!(response.text().await.unwrap(), "ok");
+
+    let proof = proof.await.unwrap();
+    assert_eq!(proof.notary_pub_key, "pubkey");
+    assert!(proof.proof.starts_with("proof-for-"));
+}
+
+#[tokio::test]
+async fn cancelled_proof_handle_errors() {
+    let app = Router::new()
+        .route("/proxy", get(proxy_handler).post(proxy_handler))
+        .route("/proof/:id", get(proof_never));
+    let client = client(serve(app).await);
+
+    let (_, proof) = client
+        .get("https://example.com/")
+        .send_detached()
+        .await
+        .unwrap();
+    proof.cancel();
+
+    assert!(matches!(proof.await, Err(VerityError::ProofCancelled)));
+}
+
+#[tokio::test]
+async fn send_all_keeps_order_and_bounds_concurrency() {
+    #[derive(Clone, Default)]
+    struct Load {
+        active: Arc<AtomicUsize>,
+        peak: Arc<AtomicUsize>,
+    }
+
+    async fn proxy_slow(State(load): State<Load>, headers: HeaderMap) -> impl IntoResponse {
+        let active = load.active.fetch_add(1, Ordering::SeqCst) + 1;
+        load.peak.fetch_max(active, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        load.active.fetch_sub(1, Ordering::SeqCst);
+
+        let target = headers
+            .get("T-PROXY-URL")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let mut headers = HeaderMap::new();
+        headers.insert("T-PROOF-ID", HeaderValue::from_static("1"));
+        (StatusCode::OK, headers, target)
+    }
+
+    async fn proof_delayed(Path(id): Path<String>) -> impl IntoResponse {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "content-type",
+            HeaderValue::from_static("text/event-stream"),
+        );
+        (headers, format!("data: pubkey|proof-for-{}\n\n", id))
+    }
+
+    let load = Load::default();
+    let app = Router::new()
+        .route("/proxy", get(proxy_slow).post(proxy_slow))
+        .route("/proof/:id", get(proof_delayed))
+        .with_state(load.clone());
+    let client = client(serve(app).await);
+
+    let requests = (0..6)
+        .map(|i| {
+            let request = client.get(format!("https://example.com/{}", i));
+            if i == 4 {
+                // Shorter than the prover's proof delay
+                request.proof_timeout(Duration::from_millis(50))
+            } else {
+                request
+            }
+        })
+        .collect();
+
+    let results = client.send_all(requests, 3).await;
+
+    assert_eq!(results.len(), 6);
+    for (i, result) in results.into_iter().enumerate() {
+        if i == 4 {
+            assert!(matches!(result, Err(VerityError::Timeout)));
+            continue;
+        }
+
+        let response = result.unwrap();
+        assert!(response.proof.starts_with("proof-for-"));
+        assert_eq!(
+            response.subject.text().await.unwrap(),
+            format!("https://example.com/{}", i)
+        );
+    }
+
+    let peak = load.peak.load(Ordering::SeqCst);
+    assert!(peak > 1 && peak <= 3, "peak concurrency was {}", peak);
+}
+
+#[tokio::test]
+async fn custom_http_client_is_used_for_all_prover_calls() {
+    type Seen = Arc<Mutex<Vec<(String, Option<String>)>>>;
+
+    fn record(seen: &Seen, path: &str, headers: &HeaderMap) {
+        let agent = headers
+            .get("user-agent")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        seen.lock().unwrap().push((path.to_string(), agent));
+    }
+
+    async fn proxy(State(seen): State<Seen>, headers: HeaderMap) -> impl IntoResponse {
+        record(&seen, "proxy", &headers);
+        proxy_handler().await
+    }
+
+    async fn proof(
+        State(seen): State<Seen>,
+        id: Path<String>,
+        headers: HeaderMap,
+    ) -> impl IntoResponse {
+        record(&seen, "proof", &headers);
+        proof_handler(id).await
+    }
+
+    async fn notary_info(State(seen): State<Seen>, headers: HeaderMap) -> impl IntoResponse {
+        record(&seen, "notaryinfo", &headers);
+        Json(notary_information("test"))
+    }
+
+    let seen = Seen::default();
+    let app = Router::new()
+        .route("/proxy", get(proxy).post(proxy))
+        .route("/proof/:id", get(proof))
+        .route("/notaryinfo", get(notary_info))
+        .with_state(seen.clone());
+
+    let http_client = reqwest::Client::builder()
+        .user_agent("verity-test-agent")
+        .connect_timeout(Duration::from_secs(1))
+        .build()
+        .unwrap();
+    let client = VerityClient::new(
+        VerityClientConfig::new(serve(app).await)
+            .with_proof_timeout(Duration::from_millis(3000))
+            .with_http_client(http_client),
+    );
+
+    client.get("https://example.com/").send().await.unwrap();
+    client.get_notary_info().await.unwrap();
+
+    let mut seen = seen.lock().unwrap().clone();
+    seen.sort();
+    let agent = Some("verity-test-agent".to_string());
+    assert_eq!(
+        seen,
+        vec![
+            ("notaryinfo".to_string(), agent.clone()),
+            ("proof".to_string(), agent.clone()),
+            ("proxy".to_string(), agent),
+        ]
+    );
+}
+
+#[test]
+fn blocking_client_proves_requests() {
+    let server_runtime = tokio::runtime::Runtime::new().unwrap();
+    let base = server_runtime.block_on(spawn_mock_server());
+
+    let client = verity_client::blocking::VerityClient::new(
+        VerityClientConfig::new(base).with_proof_timeout(Duration::from_millis(3000)),
+    );
+
+    let res = client.get("https://example.com/").send().unwrap();
+    assert!(res.notary_pub_key.starts_with("-----BEGIN PUBLIC KEY"));
+    assert!(res.proof.starts_with("proof-for-"));
+    assert_eq!(res.subject.status(), StatusCode::OK);
+    assert_eq!(res.subject.text().unwrap(), "ok");
+
+    let info = client.get_notary_info().unwrap();
+    assert_eq!(info.version, "test");
+}
+
+#[test]
+fn blocking_client_keeps_proof_timeout() {
+    let server_runtime = tokio::runtime::Runtime::new().unwrap();
+    let base = server_runtime.block_on(async {
+        let app = Router::new()
+            .route("/proxy", get(proxy_handler).post(proxy_handler))
+            .route("/proof/:id", get(proof_never));
+        serve(app).await
+    });
+
+    let client = verity_client::blocking::VerityClient::new(
+        VerityClientConfig::new(base).with_proof_timeout(Duration::from_millis(3000)),
+    );
+
+    let res = client
+        .get("https://example.com/")
+        .proof_timeout(Duration::from_millis(200))
+        .send();
+    assert!(matches!(res, Err(VerityError::Timeout)));
+}
+
+#[cfg(feature = "metrics")]
+#[tokio::test]
+async fn proof_metrics_are_recorded() {
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use verity_client::telemetry;
+
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    recorder.install().unwrap();
+
+    let client = client(spawn_mock_server().await);
+    let res = client.get("https://example.com/").send().await.unwrap();
+
+    let metrics: Vec<(String, DebugValue)> = snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .map(|(key, _, _, value)| (key.key().name().to_string(), value))
+        .collect();
+    let value = |name: &str| {
+        metrics
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value)
+    };
+
+    assert_eq!(
+        value(telemetry::PROOFS_SUCCEEDED_TOTAL),
+        Some(&DebugValue::Counter(1))
+    );
+    assert!(value(telemetry::PROOFS_FAILED_TOTAL).is_none());
+    assert!(matches!(
+        value(telemetry::RESPONSE_LATENCY_SECONDS),
+        Some(DebugValue::Histogram(samples)) if samples.len() == 1
+    ));
+    assert!(matches!(
+        value(telemetry::PROOF_SIZE_BYTES),
+        Some(DebugValue::Histogram(samples)) if samples[0].into_inner() == res.proof.len() as f64
+    ));
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use std::time::{Duration, SystemTime};
+
+use common::spawn_mock_server;
+use verity_client::client::{VerityClient, VerityClientConfig};
+use verity_client::store::FileProofStore;
+use verity_client::{ProofQuery, ProofStore, Redaction};
+
+#[tokio::test]
+async fn proofs_are_persisted_to_the_proof_store() {
+    let dir = std::env::temp_dir().join(format!("verity-proofs-{}", uuid::Uuid::new_v4()));
+    let store = FileProofStore::open(&dir).unwrap();
+
+    let client = VerityClient::new(
+        VerityClientConfig::new(spawn_mock_server().await)
+            .with_proof_timeout(Duration::from_millis(3000))
+            .with_proof_store(store.clone()),
+    );
+
+    let first = client
+        .get("https://api.example.com/accounts")
+        .redact(Redaction::response_header("set-cookie"))
+        .send()
+        .await
+        .unwrap();
+    let second = client.get("https://example.com/").send().await.unwrap();
+
+    let stored = store.get(&first.request.request_id).unwrap().unwrap();
+    assert_eq!(stored.proof, first.proof);
+    assert_eq!(stored.url, "https://api.example.com/accounts");
+    assert_eq!(stored.redactions, ["res:header:set-cookie"]);
+    assert_eq!(store.list().unwrap().len(), 2);
+
+    let by_host = store.query(&ProofQuery::new().host("example.com")).unwrap();
+    assert_eq!(by_host.len(), 1);
+    assert_eq!(by_host[0].request_id, second.request.request_id);
+
+    let later = SystemTime::now() + Duration::from_secs(60);
+    assert!(store
+        .query(&ProofQuery::new().since(later))
+        .unwrap()
+        .is_empty());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_store_queries_by_host_and_time() {
+    use std::time::UNIX_EPOCH;
+    use verity_client::store::SqliteProofStore;
+    use verity_client::ProofBundle;
+
+    let store = SqliteProofStore::open_in_memory().unwrap();
+    let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+    for (id, url, secs) in [
+        ("a", "https://api.example.com/1", 10),
+        ("b", "https://API.example.com/2", 20),
+        ("c", "https://example.com/", 30),
+    ] {
+        let bundle = ProofBundle::new("proof", "key")
+            .with_request(id, "GET", url)
+            .with_timestamp(at(secs));
+        store.put(&bundle).unwrap();
+    }
+
+    let ids = |bundles: Vec<ProofBundle>| {
+        bundles
+            .into_iter()
+            .map(|bundle| bundle.request_id)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(store.list().unwrap()), ["a", "b", "c"]);
+    assert_eq!(
+        ids(store
+            .query(&ProofQuery::new().host("api.example.com"))
+            .unwrap()),
+        ["a", "b"]
+    );
+    assert_eq!(
+        ids(store
+            .query(&ProofQuery::new().since(at(20)).until(at(30)))
+            .unwrap()),
+        ["b", "c"]
+    );
+    assert_eq!(store.get("c").unwrap().unwrap().url, "https://example.com/");
+    assert!(store.get("d").unwrap().is_none());
+}
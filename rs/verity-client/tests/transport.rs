@@ -0,0 +1,240 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::ws::{Message as WsMessage, WebSocketUpgrade};
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::get,
+};
+
+use common::{spawn_proof_server, transport_client, NOTARY_KEY};
+use verity_client::client::{VerityClient, VerityClientConfig};
+use verity_client::{ProofTransport, ProverAuth, VerityError};
+
+#[tokio::test]
+async fn websocket_transport_receives_proof() {
+    async fn proof_socket(ws: WebSocketUpgrade, Path(id): Path<String>) -> impl IntoResponse {
+        ws.on_upgrade(move |mut socket| async move {
+            // Frames without a proof are skipped
+            socket.send(WsMessage::Ping(vec![1])).await.unwrap();
+            let message = format!("{}|proof-for-{}", NOTARY_KEY, id);
+            socket.send(WsMessage::Text(message)).await.unwrap();
+            while socket.recv().await.is_some() {}
+        })
+    }
+
+    let base = spawn_proof_server(get(proof_socket)).await;
+    let client = transport_client(base, ProofTransport::WebSocket);
+
+    let res = client.get("https://example.com/").send().await.unwrap();
+    assert!(res.notary_pub_key.starts_with("-----BEGIN PUBLIC KEY"));
+    assert_eq!(res.proof, format!("proof-for-{}", res.request.request_id));
+}
+
+#[tokio::test]
+async fn websocket_transport_reconnects_dropped_sockets() {
+    async fn proof_flaky(
+        State(connections): State<Arc<AtomicUsize>>,
+        ws: WebSocketUpgrade,
+    ) -> impl IntoResponse {
+        let attempt = connections.fetch_add(1, Ordering::SeqCst);
+        ws.on_upgrade(move |mut socket| async move {
+            if attempt > 0 {
+                let message = format!("{}|proof", NOTARY_KEY);
+                socket.send(WsMessage::Text(message)).await.unwrap();
+                while socket.recv().await.is_some() {}
+            }
+        })
+    }
+
+    let connections = Arc::new(AtomicUsize::new(0));
+    let base = spawn_proof_server(get(proof_flaky).with_state(connections.clone())).await;
+    let client = transport_client(base, ProofTransport::WebSocket);
+
+    let res = client.get("https://example.com/").send().await.unwrap();
+    assert_eq!(res.proof, "proof");
+    assert_eq!(connections.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn websocket_transport_times_out() {
+    async fn proof_never(ws: WebSocketUpgrade) -> impl IntoResponse {
+        ws.on_upgrade(|mut socket| async move { while socket.recv().await.is_some() {} })
+    }
+
+    let base = spawn_proof_server(get(proof_never)).await;
+    let client = VerityClient::new(
+        VerityClientConfig::new(base)
+            .with_proof_timeout(Duration::from_millis(200))
+            .with_proof_transport(ProofTransport::WebSocket),
+    );
+
+    let res = client.get("https://example.com/").send().await;
+    assert!(matches!(res, Err(VerityError::Timeout)));
+}
+
+#[tokio::test]
+async fn websocket_transport_rejects_malformed_messages() {
+    async fn proof_malformed(ws: WebSocketUpgrade) -> impl IntoResponse {
+        ws.on_upgrade(|mut socket| async move {
+            socket
+                .send(WsMessage::Text("no-separator".to_string()))
+                .await
+                .unwrap();
+            while socket.recv().await.is_some() {}
+        })
+    }
+
+    let base = spawn_proof_server(get(proof_malformed)).await;
+    let client = transport_client(base, ProofTransport::WebSocket);
+
+    let res = client.get("https://example.com/").send().await;
+    assert!(matches!(res, Err(VerityError::InvalidProofMessage)));
+}
+
+#[tokio::test]
+async fn long_polling_transport_polls_until_proof_is_ready() {
+    async fn proof_poll(
+        State(polls): State<Arc<Mutex<Vec<HeaderMap>>>>,
+        Path(id): Path<String>,
+        headers: HeaderMap,
+    ) -> impl IntoResponse {
+        let mut polls = polls.lock().unwrap();
+        polls.push(headers);
+        if polls.len() < 3 {
+            return (StatusCode::NO_CONTENT, String::new());
+        }
+        (StatusCode::OK, format!("{}|proof-for-{}", NOTARY_KEY, id))
+    }
+
+    let polls = Arc::new(Mutex::new(Vec::new()));
+    let base = spawn_proof_server(get(proof_poll).with_state(polls.clone())).await;
+    let client = transport_client(base, ProofTransport::LongPolling);
+
+    let res = client.get("https://example.com/").send().await.unwrap();
+    assert!(res.notary_pub_key.starts_with("-----BEGIN PUBLIC KEY"));
+    assert_eq!(res.proof, format!("proof-for-{}", res.request.request_id));
+
+    let polls = polls.lock().unwrap();
+    assert_eq!(polls.len(), 3);
+    assert!(polls
+        .iter()
+        .all(|headers| headers["accept"] == "text/plain"));
+}
+
+#[tokio::test]
+async fn long_polling_transport_retries_server_errors() {
+    async fn proof_flaky(State(polls): State<Arc<AtomicUsize>>) -> impl IntoResponse {
+        if polls.fetch_add(1, Ordering::SeqCst) == 0 {
+            return (StatusCode::BAD_GATEWAY, String::new());
+        }
+        (StatusCode::OK, format!("{}|proof", NOTARY_KEY))
+    }
+
+    let polls = Arc::new(AtomicUsize::new(0));
+    let base = spawn_proof_server(get(proof_flaky).with_state(polls.clone())).await;
+    let client = transport_client(base, ProofTransport::LongPolling);
+
+    let res = client.get("https://example.com/").send().await.unwrap();
+    assert_eq!(res.proof, "proof");
+    assert_eq!(polls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn long_polling_transport_times_out() {
+    async fn proof_not_ready() -> impl IntoResponse {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        StatusCode::NO_CONTENT
+    }
+
+    let base = spawn_proof_server(get(proof_not_ready)).await;
+    let client = VerityClient::new(
+        VerityClientConfig::new(base)
+            .with_proof_timeout(Duration::from_millis(200))
+            .with_proof_transport(ProofTransport::LongPolling),
+    );
+
+    let res = client.get("https://example.com/").send().await;
+    assert!(matches!(res, Err(VerityError::Timeout)));
+}
+
+#[tokio::test]
+async fn long_polling_transport_fails_on_rejected_polls() {
+    async fn proof_unknown() -> impl IntoResponse {
+        StatusCode::NOT_FOUND
+    }
+
+    let base = spawn_proof_server(get(proof_unknown)).await;
+    let client = transport_client(base, ProofTransport::LongPolling);
+
+    let res = client.get("https://example.com/").send().await;
+    assert!(matches!(res, Err(VerityError::Prover(_))));
+}
+
+#[tokio::test]
+async fn long_polling_transport_rejects_malformed_messages() {
+    async fn proof_malformed() -> impl IntoResponse {
+        "no-separator"
+    }
+
+    let base = spawn_proof_server(get(proof_malformed)).await;
+    let client = transport_client(base, ProofTransport::LongPolling);
+
+    let res = client.get("https://example.com/").send().await;
+    assert!(matches!(res, Err(VerityError::InvalidProofMessage)));
+}
+
+#[tokio::test]
+async fn cancelled_proof_handle_stops_every_transport() {
+    async fn proof_never(ws: Option<WebSocketUpgrade>) -> axum::response::Response {
+        match ws {
+            Some(ws) => ws
+                .on_upgrade(|mut socket| async move { while socket.recv().await.is_some() {} })
+                .into_response(),
+            None => {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                StatusCode::NO_CONTENT.into_response()
+            }
+        }
+    }
+
+    let base = spawn_proof_server(get(proof_never)).await;
+    for transport in [ProofTransport::WebSocket, ProofTransport::LongPolling] {
+        let client = transport_client(base.clone(), transport);
+
+        let (_response, proof) = client
+            .get("https://example.com/")
+            .send_detached()
+            .await
+            .unwrap();
+        proof.cancel();
+
+        assert!(matches!(proof.await, Err(VerityError::ProofCancelled)));
+    }
+}
+
+#[test]
+#[should_panic(expected = "cannot present a client certificate")]
+fn websocket_transport_rejects_client_certificates() {
+    let key = rcgen::KeyPair::generate().unwrap();
+    let certificate = rcgen::CertificateParams::new(vec!["client".to_string()])
+        .unwrap()
+        .self_signed(&key)
+        .unwrap();
+    let identity = reqwest::Identity::from_pkcs8_pem(
+        certificate.pem().as_bytes(),
+        key.serialize_pem().as_bytes(),
+    )
+    .unwrap();
+
+    VerityClient::new(
+        VerityClientConfig::new("http://127.0.0.1:8080")
+            .with_proof_transport(ProofTransport::WebSocket)
+            .with_prover_auth(ProverAuth::client_certificate(identity)),
+    );
+}